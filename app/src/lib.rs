@@ -7,16 +7,27 @@ mod services;
 
 pub struct AnimalRogueProgram(());
 
+impl AnimalRogueProgram {
+    fn init(config: config::InitConfig) -> Self {
+        <admin::Service>::seed(config.admins);
+        <gold_vft::GoldService>::seed(config.gold, config.emission);
+        <item_vmt::ItemService>::seed(config.items);
+        <market::MarketService>::seed(config.prices);
+        <game::GameService>::seed(config.settings, config.verifier_keys);
+        Self(())
+    }
+}
+
 #[sails_rs::program]
 impl AnimalRogueProgram {
-    // Program's constructor
+    // Program's constructor, deploys with the default preset
     pub fn new() -> Self {
-        <admin::Service>::seed();
-        <gold_vft::GoldService>::seed();
-        <item_vmt::ItemService>::seed();
-        <market::MarketService>::seed();
-        <game::GameService>::seed();
-        Self(())
+        Self::init(config::InitConfig::default())
+    }
+
+    // Program's constructor with a network specific configuration
+    pub fn with_config(config: config::InitConfig) -> Self {
+        Self::init(config)
     }
 
     // admin service
@@ -43,4 +54,9 @@ impl AnimalRogueProgram {
     pub fn game(&self) -> game::GameService {
         game::GameService::new()
     }
+
+    // migration service
+    pub fn migration(&self) -> migration::MigrationService {
+        migration::MigrationService::new()
+    }
 }
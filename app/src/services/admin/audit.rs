@@ -0,0 +1,110 @@
+use blake2::{digest::consts::U32, Blake2b, Digest};
+use sails_rs::prelude::*;
+
+/// Entries kept before the oldest ones are overwritten.
+pub const AUDIT_LOG_CAPACITY: u32 = 1000;
+
+/// What an admin did; the parameters themselves are only kept as a hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub enum AdminActionKind {
+    AdminAdded,
+    AdminRemoved,
+    RoleGranted,
+    RoleRevoked,
+    OwnershipTransferStarted,
+    OwnershipTransferred,
+    OwnershipRenounced,
+    GovernanceUpdated,
+    ProposalCreated,
+    ProposalApproved,
+    ProposalExecuted,
+    Paused,
+    Unpaused,
+    EmergencyStopSet,
+    GoldMinted,
+    GoldBurned,
+    ItemMetadataCreated,
+    ItemsMinted,
+    ItemsBurned,
+    PriceSet,
+    SettingChangeQueued,
+    SettingChangeCancelled,
+    VerifierKeyAdded,
+    VerifierKeyExpiryUpdated,
+    VerifierKeyRevoked,
+    SeasonCreated,
+    SeasonClosed,
+    SeasonRewardsSet,
+    SeasonRewardsDistributed,
+    AccountBanned,
+    AccountUnbanned,
+    BansBlockTransfersSet,
+    MigrationModeSet,
+    SnapshotImported,
+    RateLimitSet,
+    RateLimitRemoved,
+    EpochBudgetSet,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct AuditEntry {
+    pub id: u64,
+    pub actor: ActorId,
+    pub kind: AdminActionKind,
+    /// Blake2b-256 of the SCALE-encoded call parameters.
+    pub params_hash: [u8; 32],
+    pub timestamp: u64,
+}
+
+/// Ring buffer of the most recent admin actions.
+#[derive(Debug, Default)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+    // Id of the next entry; entry `id` sits at `id % AUDIT_LOG_CAPACITY`
+    next_id: u64,
+}
+
+impl AuditLog {
+    pub fn push(
+        &mut self,
+        actor: ActorId,
+        kind: AdminActionKind,
+        params_hash: [u8; 32],
+        timestamp: u64,
+    ) -> AuditEntry {
+        let entry = AuditEntry {
+            id: self.next_id,
+            actor,
+            kind,
+            params_hash,
+            timestamp,
+        };
+        let slot = (self.next_id % u64::from(AUDIT_LOG_CAPACITY)) as usize;
+        if slot < self.entries.len() {
+            self.entries[slot] = entry.clone();
+        } else {
+            self.entries.push(entry.clone());
+        }
+        self.next_id += 1;
+        entry
+    }
+
+    pub fn len(&self) -> u32 {
+        self.entries.len() as u32
+    }
+
+    /// Retained entries from oldest to newest, starting `offset` entries in.
+    pub fn page(&self, offset: u32, limit: u32) -> Vec<AuditEntry> {
+        let oldest = self.next_id - self.entries.len() as u64;
+        (u64::from(offset)..u64::from(offset.saturating_add(limit).min(self.len())))
+            .map(|index| {
+                let slot = ((oldest + index) % u64::from(AUDIT_LOG_CAPACITY)) as usize;
+                self.entries[slot].clone()
+            })
+            .collect()
+    }
+}
+
+pub fn params_hash<P: Encode>(params: &P) -> [u8; 32] {
+    Blake2b::<U32>::digest(params.encode()).into()
+}
@@ -0,0 +1,17 @@
+use sails_rs::prelude::*;
+
+/// An account barred from playing and trading, e.g. for using a forged client.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Ban {
+    pub reason: String,
+    pub banned_by: ActorId,
+    pub banned_at: u64,
+    /// `None` bans for good.
+    pub expires_at: Option<u64>,
+}
+
+impl Ban {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.expires_at.map_or(true, |expires_at| now < expires_at)
+    }
+}
@@ -1,30 +1,418 @@
 use core::fmt::Debug;
+use gstd::exec;
 use sails_rs::{
     Vec,
+    collections::{HashMap, HashSet},
     gstd::{msg, service},
     prelude::*,
 };
+use crate::services::{
+    self,
+    game::{GameError, GameService},
+    gold_vft::{accounting::GoldSource, GoldError, GoldService},
+    market::MarketService,
+};
+pub mod audit;
+pub mod ban;
+pub mod proposal;
+pub mod rate_limit;
+pub mod snapshot;
+use audit::*;
+use ban::*;
+use proposal::*;
+use rate_limit::*;
 
 static mut ADMINS: Option<Admins> = None;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+pub enum Role {
+    /// Manages roles and implicitly holds every other role.
+    Owner,
+    /// Mints and burns GOLD.
+    Minter,
+    /// Manages item metadata and mints and burns items.
+    ItemManager,
+    /// Sets market prices.
+    PriceManager,
+    /// Tunes game settings and runs seasons.
+    GameOperator,
+    /// Registers, rotates and revokes verifier keys.
+    VerifierManager,
+}
+
+/// Parts of the program that can be paused on their own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+pub enum PauseTarget {
+    /// Purchases in the market.
+    Market,
+    /// Creating and settling games.
+    Game,
+    /// GOLD transfers between accounts.
+    GoldTransfers,
+    /// Item transfers between accounts.
+    ItemTransfers,
+}
+
+/// Roles `add_admin` hands out: everything but ownership.
+const ADMIN_ROLES: [Role; 5] = [
+    Role::Minter,
+    Role::ItemManager,
+    Role::PriceManager,
+    Role::GameOperator,
+    Role::VerifierManager,
+];
+
+/// Roles whose holders approve proposals; owner actions are approved by any admin.
+const APPROVER_ROLES: [Role; 3] = [Role::Minter, Role::PriceManager, Role::VerifierManager];
+
+type RoleMap = HashMap<ActorId, Vec<Role>>;
+
+fn grant_in(roles: &mut RoleMap, account: ActorId, role: Role) -> bool {
+    let held = roles.entry(account).or_default();
+    if held.contains(&role) {
+        return false;
+    }
+    held.push(role);
+    true
+}
+
+fn revoke_in(roles: &mut RoleMap, account: &ActorId, role: Role) -> bool {
+    let Some(held) = roles.get_mut(account) else {
+        return false;
+    };
+    let Some(pos) = held.iter().position(|r| *r == role) else {
+        return false;
+    };
+    held.remove(pos);
+    if held.is_empty() {
+        roles.remove(account);
+    }
+    true
+}
+
+// Every kind of proposal must still be able to collect `threshold` approvals:
+// owner actions from any admin, the others from the holders of their role
+fn is_reachable(roles: &RoleMap, threshold: u32) -> bool {
+    let holders = |role: Role| {
+        roles
+            .values()
+            .filter(|held| held.contains(&role) || held.contains(&Role::Owner))
+            .count() as u32
+    };
+    roles.len() as u32 >= threshold.max(1)
+        && (threshold <= 1 || APPROVER_ROLES.iter().all(|role| holders(*role) >= threshold))
+}
+
+/// Most audit log entries or bans returned by a single query.
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Proposals stay open for 3 days unless configured otherwise.
+const DEFAULT_PROPOSAL_LIFETIME: u64 = 3 * 24 * 60 * 60 * 1000;
+
 #[derive(Debug, Default)]
 pub struct Admins {
-    list: Vec<ActorId>,
+    roles: RoleMap,
+    // Account that still has to call `accept_ownership`
+    pending_owner: Option<ActorId>,
+    proposals: HashMap<u32, Proposal>,
+    last_proposal_id: u32,
+    // Approvals a proposal needs; above 1, sensitive calls only run as proposals
+    approval_threshold: u32,
+    proposal_lifetime: u64,
+    // GOLD mints above this need a proposal
+    large_mint_limit: U256,
+    paused: HashSet<PauseTarget>,
+    // Halts every pausable entry point regardless of `paused`
+    emergency_stop: bool,
+    audit_log: AuditLog,
+    bans: HashMap<ActorId, Ban>,
+    // Banned accounts also can't move their GOLD and items
+    bans_block_transfers: bool,
+    // Freezes every pausable entry point and opens the import calls
+    migration_mode: bool,
+    rate_limits: RateLimits,
 }
 
 impl Admins {
     pub fn get_mut() -> &'static mut Self {
         unsafe { ADMINS.as_mut().expect("Admins is not initialized") }
     }
-    // pub fn get() -> &'static Self {
-    //     unsafe { ADMINS.as_ref().expect("Admins is not initialized") }
-    // }
+    pub fn get() -> &'static Self {
+        unsafe { ADMINS.as_ref().expect("Admins is not initialized") }
+    }
     pub fn is_admin(account: &ActorId) -> bool {
-        let admins = unsafe { ADMINS.as_ref().expect("Admins is not initialized") };
-        admins.list.contains(account)
+        Self::get().roles.contains_key(account)
+    }
+    pub fn has_role(account: &ActorId, role: Role) -> bool {
+        Self::get()
+            .roles
+            .get(account)
+            .is_some_and(|roles| roles.contains(&role) || roles.contains(&Role::Owner))
+    }
+    /// Whether sensitive operations must be submitted as proposals.
+    pub fn requires_proposal() -> bool {
+        Self::get().approval_threshold > 1
+    }
+    pub fn large_mint_limit() -> U256 {
+        Self::get().large_mint_limit
+    }
+    pub fn is_paused(target: PauseTarget) -> bool {
+        let admins = Self::get();
+        admins.emergency_stop || admins.migration_mode || admins.paused.contains(&target)
+    }
+    /// Counts a call by `actor`, failing with the timestamp it may retry at.
+    pub fn check_rate_limit(actor: ActorId, action: RateLimitedAction) -> Result<(), u64> {
+        Self::get_mut()
+            .rate_limits
+            .hit(actor, action, exec::block_timestamp())
+    }
+    pub fn in_migration() -> bool {
+        Self::get().migration_mode
+    }
+    pub fn is_banned(account: &ActorId) -> bool {
+        Self::get()
+            .bans
+            .get(account)
+            .is_some_and(|ban| ban.is_active(exec::block_timestamp()))
+    }
+    /// Whether a ban on `account` also stops its GOLD and item transfers.
+    pub fn is_transfer_blocked(account: &ActorId) -> bool {
+        Self::get().bans_block_transfers && Self::is_banned(account)
+    }
+    /// The single holder of `Role::Owner`, if ownership was not renounced.
+    pub fn owner() -> Option<ActorId> {
+        Self::get()
+            .roles
+            .iter()
+            .find(|(_, roles)| roles.contains(&Role::Owner))
+            .map(|(account, _)| *account)
+    }
+
+    // Whether the roles after `change` can still reach the approval threshold
+    fn keeps_threshold(&self, change: impl FnOnce(&mut RoleMap)) -> bool {
+        let mut roles = self.roles.clone();
+        change(&mut roles);
+        is_reachable(&roles, self.approval_threshold)
+    }
+
+    fn grant(&mut self, account: ActorId, role: Role) -> bool {
+        grant_in(&mut self.roles, account, role)
+    }
+
+    // Checks below mirror the `*_internal` calls of the service, so a proposal
+    // can be refused with a typed error before it is recorded
+
+    fn check_add_admin(&self, admin: &ActorId) -> Result<(), AdminError> {
+        let roles = self.roles.get(admin);
+        if ADMIN_ROLES
+            .iter()
+            .all(|role| roles.is_some_and(|roles| roles.contains(role)))
+        {
+            return Err(AdminError::AlreadyAdmin);
+        }
+        Ok(())
+    }
+
+    fn check_remove_admin(&self, admin: &ActorId) -> Result<(), AdminError> {
+        // Ownership only changes hands through transfer or renounce
+        if Self::has_role(admin, Role::Owner) {
+            return Err(AdminError::CannotRemoveOwner);
+        }
+        if !Self::is_admin(admin) {
+            return Err(AdminError::UnknownAdmin);
+        }
+        if !self.keeps_threshold(|roles| {
+            roles.remove(admin);
+        }) {
+            return Err(AdminError::LastAdmin);
+        }
+        Ok(())
+    }
+
+    fn check_grant_role(&self, account: &ActorId, role: Role) -> Result<(), AdminError> {
+        if role == Role::Owner {
+            return Err(AdminError::OwnerRoleReserved);
+        }
+        if self.roles.get(account).is_some_and(|roles| roles.contains(&role)) {
+            return Err(AdminError::RoleAlreadyGranted);
+        }
+        Ok(())
+    }
+
+    fn check_revoke_role(&self, account: &ActorId, role: Role) -> Result<(), AdminError> {
+        if role == Role::Owner {
+            return Err(AdminError::OwnerRoleReserved);
+        }
+        let roles = self.roles.get(account).ok_or(AdminError::RoleNotGranted)?;
+        if !roles.contains(&role) {
+            return Err(AdminError::RoleNotGranted);
+        }
+        // Revoking an account's only role also removes it as an admin
+        if !self.keeps_threshold(|roles| {
+            revoke_in(roles, account, role);
+        }) {
+            return Err(AdminError::LastAdmin);
+        }
+        Ok(())
+    }
+
+    fn check_propose_owner(&self, new_owner: &ActorId) -> Result<ActorId, AdminError> {
+        let previous_owner = Self::owner().ok_or(AdminError::NoOwner)?;
+        if *new_owner == previous_owner || *new_owner == ActorId::zero() {
+            return Err(AdminError::InvalidNewOwner);
+        }
+        self.check_ownership_transfer(&previous_owner, new_owner)?;
+        Ok(previous_owner)
+    }
+
+    // Handing over `Owner` drops the previous owner as an admin if that was its
+    // only role, which must leave enough admins to reach the threshold
+    fn check_ownership_transfer(
+        &self,
+        previous_owner: &ActorId,
+        new_owner: &ActorId,
+    ) -> Result<(), AdminError> {
+        if !self.keeps_threshold(|roles| {
+            revoke_in(roles, previous_owner, Role::Owner);
+            grant_in(roles, *new_owner, Role::Owner);
+        }) {
+            return Err(AdminError::LastAdmin);
+        }
+        Ok(())
+    }
+
+    fn check_renounce_ownership(&self) -> Result<ActorId, AdminError> {
+        let previous_owner = Self::owner().ok_or(AdminError::NoOwner)?;
+        // Leaving no admins at all is the deliberate point of renouncing, but
+        // the remaining ones must still be able to reach the threshold
+        if self.approval_threshold > 1
+            && !self.keeps_threshold(|roles| {
+                revoke_in(roles, &previous_owner, Role::Owner);
+            })
+        {
+            return Err(AdminError::LastAdmin);
+        }
+        Ok(previous_owner)
+    }
+
+    fn check_governance(&self, approval_threshold: u32) -> Result<(), AdminError> {
+        // A threshold nobody can reach would lock every sensitive operation
+        if approval_threshold == 0 || !is_reachable(&self.roles, approval_threshold) {
+            return Err(AdminError::InvalidThreshold);
+        }
+        Ok(())
+    }
+
+    fn check_unpause(&self, target: &PauseTarget) -> Result<(), AdminError> {
+        if !self.paused.contains(target) {
+            return Err(AdminError::NotPaused);
+        }
+        Ok(())
+    }
+
+    fn check_emergency_stop(&self, active: bool) -> Result<(), AdminError> {
+        if self.emergency_stop == active {
+            return Err(AdminError::EmergencyStopUnchanged);
+        }
+        Ok(())
+    }
+
+    fn check_migration_mode(&self, active: bool) -> Result<(), AdminError> {
+        if self.migration_mode == active {
+            return Err(AdminError::MigrationModeUnchanged);
+        }
+        Ok(())
+    }
+
+    /// Whether `action` would run right now.
+    fn check_action(&self, action: &ProposalAction) -> Result<(), AdminError> {
+        match action {
+            ProposalAction::AddAdmin { admin } => self.check_add_admin(admin),
+            ProposalAction::RemoveAdmin { admin } => self.check_remove_admin(admin),
+            ProposalAction::GrantRole { account, role } => self.check_grant_role(account, *role),
+            ProposalAction::RevokeRole { account, role } => self.check_revoke_role(account, *role),
+            ProposalAction::ProposeOwner { new_owner } => {
+                self.check_propose_owner(new_owner).map(|_| ())
+            }
+            ProposalAction::RenounceOwnership => self.check_renounce_ownership().map(|_| ()),
+            ProposalAction::AddVerifierKey {
+                key_id,
+                public_key,
+                activated_at,
+                expires_at,
+            } => GameService::new()
+                .check_new_verifier_key(*key_id, public_key, *activated_at, *expires_at)
+                .map(|_| ())
+                .map_err(AdminError::Game),
+            ProposalAction::SetVerifierKeyExpiry { key_id, expires_at } => GameService::new()
+                .check_verifier_key_expiry(*key_id, *expires_at)
+                .map_err(AdminError::Game),
+            ProposalAction::RevokeVerifierKey { key_id } => GameService::new()
+                .check_verifier_key_exists(*key_id)
+                .map_err(AdminError::Game),
+            ProposalAction::Mint { value, .. } => {
+                GoldService::check_mint(*value).map_err(AdminError::Gold)
+            }
+            ProposalAction::SetGovernance {
+                approval_threshold, ..
+            } => self.check_governance(*approval_threshold),
+            ProposalAction::SetMigrationMode { active } => self.check_migration_mode(*active),
+            ProposalAction::Unpause { target } => self.check_unpause(target),
+            ProposalAction::LiftEmergencyStop => self.check_emergency_stop(false),
+            ProposalAction::SetPrice { .. } | ProposalAction::SetEpochBudget { .. } => Ok(()),
+        }
+    }
+
+    fn revoke(&mut self, account: &ActorId, role: Role) -> bool {
+        revoke_in(&mut self.roles, account, role)
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum AdminError {
+    /// The caller does not own the program.
+    NotOwner,
+    /// The caller holds no role.
+    NotAdmin,
+    /// Approvals are required, submit the call through `propose`.
+    ProposalRequired,
+    /// The account holds no role.
+    UnknownAdmin,
+    AlreadyAdmin,
+    RoleAlreadyGranted,
+    RoleNotGranted,
+    /// `Owner` only changes hands through an ownership transfer.
+    OwnerRoleReserved,
+    CannotRemoveOwner,
+    /// Too few admins, or holders of an approving role, would remain to
+    /// reach the approval threshold.
+    LastAdmin,
+    InvalidThreshold,
+    NoOwner,
+    InvalidNewOwner,
+    NotPendingOwner,
+    ProposalNotFound,
+    ProposalNotPending,
+    AlreadyApproved,
+    AlreadyPaused,
+    NotPaused,
+    EmergencyStopUnchanged,
+    /// Admins can't be banned, remove them first.
+    CannotBanAdmin,
+    /// The ban would already be over.
+    InvalidBanExpiry,
+    NotBanned,
+    MigrationModeUnchanged,
+    MissingRole,
+    /// Limits need at least one call and a non-empty window.
+    InvalidRateLimit,
+    RateLimitNotSet,
+    /// The proposed game call would fail.
+    Game(GameError),
+    /// The proposed GOLD call would fail.
+    Gold(GoldError),
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
 pub enum Event {
     AdminAdded {
@@ -33,19 +421,119 @@ pub enum Event {
     AdminRemoved {
         admin: ActorId,
     },
+    RoleGranted {
+        account: ActorId,
+        role: Role,
+    },
+    RoleRevoked {
+        account: ActorId,
+        role: Role,
+    },
+    ProposalCreated {
+        proposal_id: u32,
+        proposer: ActorId,
+    },
+    ProposalApproved {
+        proposal_id: u32,
+        approver: ActorId,
+        approvals: u32,
+    },
+    ProposalExecuted {
+        proposal_id: u32,
+    },
+    GovernanceUpdated {
+        approval_threshold: u32,
+        proposal_lifetime: u64,
+        large_mint_limit: U256,
+    },
+    OwnershipTransferStarted {
+        previous_owner: ActorId,
+        new_owner: ActorId,
+    },
+    OwnershipTransferred {
+        previous_owner: ActorId,
+        new_owner: ActorId,
+    },
+    OwnershipRenounced {
+        previous_owner: ActorId,
+    },
+    Paused {
+        target: PauseTarget,
+    },
+    Unpaused {
+        target: PauseTarget,
+    },
+    EmergencyStopSet {
+        active: bool,
+    },
+    /// Also emitted when an existing ban is replaced.
+    Banned {
+        account: ActorId,
+        reason: String,
+        expires_at: Option<u64>,
+        banned_by: ActorId,
+    },
+    Unbanned {
+        account: ActorId,
+        unbanned_by: ActorId,
+    },
+    BansBlockTransfersSet {
+        active: bool,
+    },
+    MigrationModeSet {
+        active: bool,
+    },
+    RateLimitSet {
+        action: RateLimitedAction,
+        limit: RateLimit,
+    },
+    RateLimitRemoved {
+        action: RateLimitedAction,
+    },
+    /// Emitted next to the specific event for every admin action, mirroring
+    /// the audit log entry.
+    AdminAction {
+        actor: ActorId,
+        kind: AdminActionKind,
+        params_hash: [u8; 32],
+        timestamp: u64,
+    },
+}
+
+/// Records an admin action performed by the caller of the current message.
+pub fn record_admin_action<P: Encode>(kind: AdminActionKind, params: &P) {
+    Service::new().record(kind, params);
 }
 
 #[derive(Clone)]
 pub struct Service();
 
 impl Service {
-    pub fn seed() -> Self {
+    pub fn seed(initial_admins: Vec<ActorId>) -> Self {
         unsafe {
             let mut admins = Admins {
-                list: Vec::new(),
+                roles: HashMap::new(),
+                pending_owner: None,
+                proposals: HashMap::new(),
+                last_proposal_id: 0,
+                approval_threshold: 1,
+                proposal_lifetime: DEFAULT_PROPOSAL_LIFETIME,
+                large_mint_limit: U256::MAX,
+                paused: HashSet::new(),
+                emergency_stop: false,
+                audit_log: AuditLog::default(),
+                bans: HashMap::new(),
+                bans_block_transfers: false,
+                migration_mode: false,
+                rate_limits: RateLimits::default(),
             };
             let deployer = msg::source();
-            admins.list.push(deployer); 
+            admins.grant(deployer, Role::Owner);
+            for admin in initial_admins.into_iter().filter(|admin| *admin != deployer) {
+                for role in ADMIN_ROLES {
+                    admins.grant(admin, role);
+                }
+            }
             ADMINS = Some(admins);
         }
         Self()
@@ -58,37 +546,656 @@ impl Service {
         Self()
     }
 
-    pub fn add_admin(&mut self, admin: ActorId) -> bool {
-        let caller = msg::source();
-        if !Admins::is_admin(&caller) {
-            return false;
+    /// Grants every role except `Owner`.
+    pub fn add_admin(&mut self, admin: ActorId) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.add_admin_internal(admin)
+    }
+
+    /// Takes away every role `admin` holds. Refused for the owner and for
+    /// the last remaining admin.
+    pub fn remove_admin(&mut self, admin: ActorId) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.remove_admin_internal(admin)
+    }
+
+    pub fn grant_role(&mut self, account: ActorId, role: Role) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.grant_role_internal(account, role)
+    }
+
+    pub fn revoke_role(&mut self, account: ActorId, role: Role) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.revoke_role_internal(account, role)
+    }
+
+    /// First step of an ownership transfer; `new_owner` takes over once it
+    /// calls `accept_ownership`. Proposing again replaces the pending owner.
+    pub fn propose_owner(&mut self, new_owner: ActorId) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.propose_owner_internal(new_owner)
+    }
+
+    /// Second step of an ownership transfer, called by the proposed owner.
+    pub fn accept_ownership(&mut self) -> Result<(), AdminError> {
+        let new_owner = msg::source();
+        let admins = Admins::get_mut();
+        if admins.pending_owner != Some(new_owner) {
+            return Err(AdminError::NotPendingOwner);
+        }
+        let previous_owner = Admins::owner().ok_or(AdminError::NoOwner)?;
+        admins.check_ownership_transfer(&previous_owner, &new_owner)?;
+        admins.pending_owner = None;
+        admins.revoke(&previous_owner, Role::Owner);
+        admins.grant(new_owner, Role::Owner);
+        self.notify_on(Event::OwnershipTransferred {
+            previous_owner,
+            new_owner,
+        })
+        .expect("Notification Error");
+        self.record(AdminActionKind::OwnershipTransferred, &previous_owner);
+        Ok(())
+    }
+
+    /// Gives up ownership for good: afterwards nobody can manage roles.
+    pub fn renounce_ownership(&mut self) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.renounce_ownership_internal()
+    }
+
+    pub fn owner(&self) -> Option<ActorId> {
+        Admins::owner()
+    }
+
+    pub fn pending_owner(&self) -> Option<ActorId> {
+        Admins::get().pending_owner
+    }
+
+    /// Changes the multi-signature settings directly, which is only possible
+    /// while a single approval suffices.
+    pub fn set_governance(
+        &mut self,
+        approval_threshold: u32,
+        proposal_lifetime: u64,
+        large_mint_limit: U256,
+    ) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.set_governance_internal(approval_threshold, proposal_lifetime, large_mint_limit)
+    }
+
+    /// Submits a sensitive operation. The proposer's approval counts, so with
+    /// a threshold of 1 it runs right away. Returns the proposal id.
+    pub fn propose(&mut self, action: ProposalAction) -> Result<u32, AdminError> {
+        let proposer = msg::source();
+        if !Admins::is_admin(&proposer) {
+            return Err(AdminError::NotAdmin);
         }
+        if !Admins::has_role(&proposer, action.required_role()) {
+            return Err(AdminError::MissingRole);
+        }
+        Admins::get().check_action(&action)?;
         let admins = Admins::get_mut();
-        if !admins.list.contains(&admin) {
-            admins.list.push(admin);
-            self.notify_on(Event::AdminAdded { admin }).expect("Notification Error");
-            true
-        } else {
-            false
+        admins.last_proposal_id += 1;
+        let proposal_id = admins.last_proposal_id;
+        let now = exec::block_timestamp();
+        admins.proposals.insert(
+            proposal_id,
+            Proposal {
+                id: proposal_id,
+                action,
+                proposer,
+                approvals: vec![proposer],
+                created_at: now,
+                expires_at: now + admins.proposal_lifetime,
+                executed: false,
+            },
+        );
+        self.notify_on(Event::ProposalCreated {
+            proposal_id,
+            proposer,
+        })
+        .expect("Notification Error");
+        self.record(
+            AdminActionKind::ProposalCreated,
+            &(proposal_id, &admins.proposals[&proposal_id].action),
+        );
+
+        if admins.approval_threshold <= 1 {
+            self.execute(proposal_id);
         }
+        Ok(proposal_id)
     }
 
-    pub fn remove_admin(&mut self, admin: ActorId) -> bool {
-        let caller = msg::source();
-        if !Admins::is_admin(&caller) {
-            return false;
+    /// Adds the caller's approval, running the proposal once the threshold is met.
+    pub fn approve(&mut self, proposal_id: u32) -> Result<(), AdminError> {
+        let approver = msg::source();
+        if !Admins::is_admin(&approver) {
+            return Err(AdminError::NotAdmin);
         }
         let admins = Admins::get_mut();
-        if let Some(pos) = admins.list.iter().position(|x| *x == admin) {
-            admins.list.remove(pos);
-            self.notify_on(Event::AdminRemoved { admin }).expect("Notification Error");
-            true
+        let proposal = admins
+            .proposals
+            .get_mut(&proposal_id)
+            .ok_or(AdminError::ProposalNotFound)?;
+        if !proposal.is_pending(exec::block_timestamp()) {
+            return Err(AdminError::ProposalNotPending);
+        }
+        if proposal
+            .action
+            .approver_role()
+            .is_some_and(|role| !Admins::has_role(&approver, role))
+        {
+            return Err(AdminError::MissingRole);
+        }
+        if proposal.approvals.contains(&approver) {
+            return Err(AdminError::AlreadyApproved);
+        }
+        Admins::get().check_action(&proposal.action)?;
+        proposal.approvals.push(approver);
+        let approvals = proposal.approvals.len() as u32;
+        self.notify_on(Event::ProposalApproved {
+            proposal_id,
+            approver,
+            approvals,
+        })
+        .expect("Notification Error");
+        self.record(AdminActionKind::ProposalApproved, &proposal_id);
+
+        if approvals >= admins.approval_threshold {
+            self.execute(proposal_id);
+        }
+        Ok(())
+    }
+
+    pub fn get_proposal(&self, proposal_id: u32) -> Option<Proposal> {
+        Admins::get().proposals.get(&proposal_id).cloned()
+    }
+
+    pub fn get_pending_proposals(&self) -> Vec<Proposal> {
+        let now = exec::block_timestamp();
+        let mut proposals: Vec<Proposal> = Admins::get()
+            .proposals
+            .values()
+            .filter(|proposal| proposal.is_pending(now))
+            .cloned()
+            .collect();
+        proposals.sort_by_key(|proposal| proposal.id);
+        proposals
+    }
+
+    /// Current `(approval_threshold, proposal_lifetime, large_mint_limit)`.
+    pub fn get_governance(&self) -> (u32, u64, U256) {
+        let admins = Admins::get();
+        (
+            admins.approval_threshold,
+            admins.proposal_lifetime,
+            admins.large_mint_limit,
+        )
+    }
+
+    /// Any admin can pause, so an exploit can be stopped without waiting for
+    /// the owner or for proposal approvals.
+    pub fn pause(&mut self, target: PauseTarget) -> Result<(), AdminError> {
+        self.ensure_admin()?;
+        if !Admins::get_mut().paused.insert(target) {
+            return Err(AdminError::AlreadyPaused);
+        }
+        self.notify_on(Event::Paused { target })
+            .expect("Notification Error");
+        self.record(AdminActionKind::Paused, &target);
+        Ok(())
+    }
+
+    /// Any admin can pause, resuming is up to the owner.
+    pub fn unpause(&mut self, target: PauseTarget) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.unpause_internal(target)
+    }
+
+    /// Halts every pausable service at once, or lifts the halt. Any admin
+    /// can halt, only the owner lifts it.
+    pub fn set_emergency_stop(&mut self, active: bool) -> Result<(), AdminError> {
+        if active {
+            self.ensure_admin()?;
         } else {
-            false
+            self.ensure_can_act_directly()?;
+        }
+        self.set_emergency_stop_internal(active)
+    }
+
+    /// Whether `target` currently rejects calls, directly or through the
+    /// emergency stop.
+    pub fn is_paused(&self, target: PauseTarget) -> bool {
+        Admins::is_paused(target)
+    }
+
+    pub fn is_emergency_stopped(&self) -> bool {
+        Admins::get().emergency_stop
+    }
+
+    /// Freezes the program for an upgrade: every pausable call is rejected,
+    /// and so is every player call that changes exported state (registering,
+    /// profile and session key changes, expiring games, claiming vested GOLD,
+    /// GOLD approvals), so export pages stay consistent. Admin calls still
+    /// run, and the owner can import a snapshot through the migration service.
+    pub fn set_migration_mode(&mut self, active: bool) -> Result<(), AdminError> {
+        self.ensure_can_act_directly()?;
+        self.set_migration_mode_internal(active)
+    }
+
+    pub fn is_migration_mode(&self) -> bool {
+        Admins::in_migration()
+    }
+
+    /// Bars `account` from games and the market until `expires_at`, or for
+    /// good. Banning an account again replaces its ban.
+    pub fn ban(
+        &mut self,
+        account: ActorId,
+        reason: String,
+        expires_at: Option<u64>,
+    ) -> Result<(), AdminError> {
+        self.ensure_admin()?;
+        if Admins::is_admin(&account) {
+            return Err(AdminError::CannotBanAdmin);
+        }
+        let now = exec::block_timestamp();
+        if expires_at.is_some_and(|expires_at| expires_at <= now) {
+            return Err(AdminError::InvalidBanExpiry);
         }
+        let banned_by = msg::source();
+        Admins::get_mut().bans.insert(
+            account,
+            Ban {
+                reason: reason.clone(),
+                banned_by,
+                banned_at: now,
+                expires_at,
+            },
+        );
+        self.notify_on(Event::Banned {
+            account,
+            reason: reason.clone(),
+            expires_at,
+            banned_by,
+        })
+        .expect("Notification Error");
+        self.record(AdminActionKind::AccountBanned, &(account, reason, expires_at));
+        Ok(())
+    }
+
+    pub fn unban(&mut self, account: ActorId) -> Result<(), AdminError> {
+        self.ensure_admin()?;
+        if !Admins::is_banned(&account) {
+            return Err(AdminError::NotBanned);
+        }
+        Admins::get_mut().bans.remove(&account);
+        self.notify_on(Event::Unbanned {
+            account,
+            unbanned_by: msg::source(),
+        })
+        .expect("Notification Error");
+        self.record(AdminActionKind::AccountUnbanned, &account);
+        Ok(())
+    }
+
+    /// Chooses whether bans also freeze GOLD and item transfers.
+    pub fn set_bans_block_transfers(&mut self, active: bool) -> Result<(), AdminError> {
+        self.ensure_admin()?;
+        Admins::get_mut().bans_block_transfers = active;
+        self.notify_on(Event::BansBlockTransfersSet { active })
+            .expect("Notification Error");
+        self.record(AdminActionKind::BansBlockTransfersSet, &active);
+        Ok(())
+    }
+
+    /// Throttles `action` for every actor, replacing any previous limit and
+    /// starting all counters afresh.
+    pub fn set_rate_limit(
+        &mut self,
+        action: RateLimitedAction,
+        limit: RateLimit,
+    ) -> Result<(), AdminError> {
+        self.ensure_role(Role::GameOperator)?;
+        if limit.max_calls == 0 || limit.window == 0 {
+            return Err(AdminError::InvalidRateLimit);
+        }
+        let rate_limits = &mut Admins::get_mut().rate_limits;
+        rate_limits.limits.insert(action, limit);
+        rate_limits.reset(action);
+        self.notify_on(Event::RateLimitSet { action, limit })
+            .expect("Notification Error");
+        self.record(AdminActionKind::RateLimitSet, &(action, limit));
+        Ok(())
+    }
+
+    pub fn remove_rate_limit(&mut self, action: RateLimitedAction) -> Result<(), AdminError> {
+        self.ensure_role(Role::GameOperator)?;
+        let rate_limits = &mut Admins::get_mut().rate_limits;
+        if rate_limits.limits.remove(&action).is_none() {
+            return Err(AdminError::RateLimitNotSet);
+        }
+        rate_limits.reset(action);
+        self.notify_on(Event::RateLimitRemoved { action })
+            .expect("Notification Error");
+        self.record(AdminActionKind::RateLimitRemoved, &action);
+        Ok(())
+    }
+
+    pub fn get_rate_limits(&self) -> Vec<(RateLimitedAction, RateLimit)> {
+        let mut limits: Vec<(RateLimitedAction, RateLimit)> = Admins::get()
+            .rate_limits
+            .limits
+            .iter()
+            .map(|(action, limit)| (*action, *limit))
+            .collect();
+        limits.sort();
+        limits
+    }
+
+    pub fn get_ban(&self, account: ActorId) -> Option<Ban> {
+        let now = exec::block_timestamp();
+        Admins::get()
+            .bans
+            .get(&account)
+            .filter(|ban| ban.is_active(now))
+            .cloned()
+    }
+
+    /// Bans still in force ordered by account, at most 100 per call.
+    pub fn get_bans(&self, offset: u32, limit: u32) -> Vec<(ActorId, Ban)> {
+        let now = exec::block_timestamp();
+        let mut bans: Vec<(ActorId, Ban)> = Admins::get()
+            .bans
+            .iter()
+            .filter(|(_, ban)| ban.is_active(now))
+            .map(|(account, ban)| (*account, ban.clone()))
+            .collect();
+        bans.sort_by_key(|(account, _)| *account);
+        bans.into_iter()
+            .skip(offset as usize)
+            .take(limit.min(MAX_PAGE_SIZE) as usize)
+            .collect()
+    }
+
+    pub fn bans_block_transfers(&self) -> bool {
+        Admins::get().bans_block_transfers
+    }
+
+    /// Retained audit log entries from oldest to newest, at most 100 per call.
+    pub fn audit_log(&self, offset: u32, limit: u32) -> Vec<AuditEntry> {
+        Admins::get().audit_log.page(offset, limit.min(MAX_PAGE_SIZE))
+    }
+
+    pub fn audit_log_len(&self) -> u32 {
+        Admins::get().audit_log.len()
+    }
+
+    pub fn roles_of(&self, account: ActorId) -> Vec<Role> {
+        Admins::get().roles.get(&account).cloned().unwrap_or_default()
     }
 
     pub fn is_admin(&self, account: ActorId) -> bool {
         Admins::is_admin(&account)
     }
-}
\ No newline at end of file
+}
+
+impl Service {
+    fn ensure_role(&self, role: Role) -> Result<(), AdminError> {
+        if !Admins::has_role(&msg::source(), role) {
+            return Err(AdminError::MissingRole);
+        }
+        Ok(())
+    }
+
+    fn ensure_admin(&self) -> Result<(), AdminError> {
+        if !Admins::is_admin(&msg::source()) {
+            return Err(AdminError::NotAdmin);
+        }
+        Ok(())
+    }
+
+    // Owner calls that skip the proposal flow while a single approval suffices
+    fn ensure_can_act_directly(&self) -> Result<(), AdminError> {
+        if !Admins::has_role(&msg::source(), Role::Owner) {
+            return Err(AdminError::NotOwner);
+        }
+        if Admins::requires_proposal() {
+            return Err(AdminError::ProposalRequired);
+        }
+        Ok(())
+    }
+
+    fn unpause_internal(&mut self, target: PauseTarget) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_unpause(&target)?;
+        admins.paused.remove(&target);
+        self.notify_on(Event::Unpaused { target })
+            .expect("Notification Error");
+        self.record(AdminActionKind::Unpaused, &target);
+        Ok(())
+    }
+
+    fn set_emergency_stop_internal(&mut self, active: bool) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_emergency_stop(active)?;
+        admins.emergency_stop = active;
+        self.notify_on(Event::EmergencyStopSet { active })
+            .expect("Notification Error");
+        self.record(AdminActionKind::EmergencyStopSet, &active);
+        Ok(())
+    }
+
+    fn set_migration_mode_internal(&mut self, active: bool) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_migration_mode(active)?;
+        admins.migration_mode = active;
+        self.notify_on(Event::MigrationModeSet { active })
+            .expect("Notification Error");
+        self.record(AdminActionKind::MigrationModeSet, &active);
+        Ok(())
+    }
+
+    fn add_admin_internal(&mut self, admin: ActorId) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_add_admin(&admin)?;
+        for role in ADMIN_ROLES {
+            if admins.grant(admin, role) {
+                self.notify_on(Event::RoleGranted { account: admin, role })
+                    .expect("Notification Error");
+            }
+        }
+        self.notify_on(Event::AdminAdded { admin }).expect("Notification Error");
+        self.record(AdminActionKind::AdminAdded, &admin);
+        Ok(())
+    }
+
+    fn remove_admin_internal(&mut self, admin: ActorId) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_remove_admin(&admin)?;
+        let roles = admins.roles.remove(&admin).unwrap_or_default();
+        for role in roles {
+            self.notify_on(Event::RoleRevoked { account: admin, role })
+                .expect("Notification Error");
+        }
+        self.notify_on(Event::AdminRemoved { admin }).expect("Notification Error");
+        self.record(AdminActionKind::AdminRemoved, &admin);
+        Ok(())
+    }
+
+    fn grant_role_internal(&mut self, account: ActorId, role: Role) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_grant_role(&account, role)?;
+        admins.grant(account, role);
+        self.notify_on(Event::RoleGranted { account, role })
+            .expect("Notification Error");
+        self.record(AdminActionKind::RoleGranted, &(account, role));
+        Ok(())
+    }
+
+    fn revoke_role_internal(&mut self, account: ActorId, role: Role) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_revoke_role(&account, role)?;
+        admins.revoke(&account, role);
+        self.notify_on(Event::RoleRevoked { account, role })
+            .expect("Notification Error");
+        self.record(AdminActionKind::RoleRevoked, &(account, role));
+        Ok(())
+    }
+
+    fn propose_owner_internal(&mut self, new_owner: ActorId) -> Result<(), AdminError> {
+        let previous_owner = Admins::get().check_propose_owner(&new_owner)?;
+        Admins::get_mut().pending_owner = Some(new_owner);
+        self.notify_on(Event::OwnershipTransferStarted {
+            previous_owner,
+            new_owner,
+        })
+        .expect("Notification Error");
+        self.record(AdminActionKind::OwnershipTransferStarted, &new_owner);
+        Ok(())
+    }
+
+    fn renounce_ownership_internal(&mut self) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        let previous_owner = admins.check_renounce_ownership()?;
+        admins.revoke(&previous_owner, Role::Owner);
+        admins.pending_owner = None;
+        self.notify_on(Event::OwnershipRenounced { previous_owner })
+            .expect("Notification Error");
+        self.record(AdminActionKind::OwnershipRenounced, &previous_owner);
+        Ok(())
+    }
+
+    fn set_governance_internal(
+        &mut self,
+        approval_threshold: u32,
+        proposal_lifetime: u64,
+        large_mint_limit: U256,
+    ) -> Result<(), AdminError> {
+        let admins = Admins::get_mut();
+        admins.check_governance(approval_threshold)?;
+        admins.approval_threshold = approval_threshold;
+        admins.proposal_lifetime = proposal_lifetime;
+        admins.large_mint_limit = large_mint_limit;
+        self.notify_on(Event::GovernanceUpdated {
+            approval_threshold,
+            proposal_lifetime,
+            large_mint_limit,
+        })
+        .expect("Notification Error");
+        self.record(
+            AdminActionKind::GovernanceUpdated,
+            &(approval_threshold, proposal_lifetime, large_mint_limit),
+        );
+        Ok(())
+    }
+
+    /// Appends to the audit log and emits `AdminAction`.
+    pub fn record<P: Encode>(&mut self, kind: AdminActionKind, params: &P) {
+        let entry = Admins::get_mut().audit_log.push(
+            msg::source(),
+            kind,
+            audit::params_hash(params),
+            exec::block_timestamp(),
+        );
+        // Other services record their actions too, emit under the Admin route
+        services::utils::on_route(services::utils::ADMIN_ROUTE, || {
+            self.notify_on(Event::AdminAction {
+                actor: entry.actor,
+                kind: entry.kind,
+                params_hash: entry.params_hash,
+                timestamp: entry.timestamp,
+            })
+        })
+        .expect("Notification Error");
+    }
+
+    // Runs an approved proposal. Actions are checked when proposed and approved,
+    // so a failing one only panics when state changed in between; the panic
+    // rolls back the approval that triggered it.
+    fn execute(&mut self, proposal_id: u32) {
+        let proposal = Admins::get_mut()
+            .proposals
+            .get_mut(&proposal_id)
+            .expect("Proposal not found");
+        proposal.executed = true;
+        let action = proposal.action.clone();
+        self.record(AdminActionKind::ProposalExecuted, &(proposal_id, &action));
+
+        match action {
+            ProposalAction::AddAdmin { admin } => {
+                services::utils::panicking(|| self.add_admin_internal(admin))
+            }
+            ProposalAction::RemoveAdmin { admin } => {
+                services::utils::panicking(|| self.remove_admin_internal(admin))
+            }
+            ProposalAction::GrantRole { account, role } => {
+                services::utils::panicking(|| self.grant_role_internal(account, role))
+            }
+            ProposalAction::RevokeRole { account, role } => {
+                services::utils::panicking(|| self.revoke_role_internal(account, role))
+            }
+            ProposalAction::ProposeOwner { new_owner } => {
+                services::utils::panicking(|| self.propose_owner_internal(new_owner))
+            }
+            ProposalAction::RenounceOwnership => {
+                services::utils::panicking(|| self.renounce_ownership_internal())
+            }
+            ProposalAction::AddVerifierKey {
+                key_id,
+                public_key,
+                activated_at,
+                expires_at,
+            } => services::utils::panicking(|| {
+                GameService::new().add_verifier_key_internal(
+                    key_id,
+                    public_key,
+                    activated_at,
+                    expires_at,
+                )
+            }),
+            ProposalAction::SetVerifierKeyExpiry { key_id, expires_at } => {
+                services::utils::panicking(|| {
+                    GameService::new().set_verifier_key_expiry_internal(key_id, expires_at)
+                })
+            }
+            ProposalAction::RevokeVerifierKey { key_id } => services::utils::panicking(|| {
+                GameService::new().revoke_verifier_key_internal(key_id)
+            }),
+            ProposalAction::Mint { to, value } => {
+                services::utils::panicking(|| {
+                    GoldService::new().mint_internal(to, value, GoldSource::AdminMint)
+                });
+            }
+            ProposalAction::SetPrice { token_id, price } => {
+                MarketService::new().set_price_internal(token_id, price);
+            }
+            ProposalAction::SetGovernance {
+                approval_threshold,
+                proposal_lifetime,
+                large_mint_limit,
+            } => services::utils::panicking(|| {
+                self.set_governance_internal(
+                    approval_threshold,
+                    proposal_lifetime,
+                    large_mint_limit,
+                )
+            }),
+            ProposalAction::SetMigrationMode { active } => {
+                services::utils::panicking(|| self.set_migration_mode_internal(active))
+            }
+            ProposalAction::SetEpochBudget { epoch_budget } => {
+                GoldService::new().set_epoch_budget_internal(epoch_budget);
+            }
+            ProposalAction::Unpause { target } => {
+                services::utils::panicking(|| self.unpause_internal(target))
+            }
+            ProposalAction::LiftEmergencyStop => {
+                services::utils::panicking(|| self.set_emergency_stop_internal(false))
+            }
+        }
+
+        self.notify_on(Event::ProposalExecuted { proposal_id })
+            .expect("Notification Error");
+    }
+}
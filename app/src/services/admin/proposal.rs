@@ -0,0 +1,111 @@
+use super::{PauseTarget, Role};
+use sails_rs::prelude::*;
+use vmt_service::utils::TokenId;
+
+/// Sensitive operations that go through multi-signature approval.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum ProposalAction {
+    AddAdmin {
+        admin: ActorId,
+    },
+    RemoveAdmin {
+        admin: ActorId,
+    },
+    GrantRole {
+        account: ActorId,
+        role: Role,
+    },
+    RevokeRole {
+        account: ActorId,
+        role: Role,
+    },
+    ProposeOwner {
+        new_owner: ActorId,
+    },
+    RenounceOwnership,
+    AddVerifierKey {
+        key_id: u32,
+        public_key: Vec<u8>,
+        activated_at: u64,
+        expires_at: Option<u64>,
+    },
+    SetVerifierKeyExpiry {
+        key_id: u32,
+        expires_at: Option<u64>,
+    },
+    RevokeVerifierKey {
+        key_id: u32,
+    },
+    Mint {
+        to: ActorId,
+        value: U256,
+    },
+    SetPrice {
+        token_id: TokenId,
+        price: U256,
+    },
+    SetGovernance {
+        approval_threshold: u32,
+        proposal_lifetime: u64,
+        large_mint_limit: U256,
+    },
+    SetMigrationMode {
+        active: bool,
+    },
+    SetEpochBudget {
+        epoch_budget: U256,
+    },
+    Unpause {
+        target: PauseTarget,
+    },
+    LiftEmergencyStop,
+}
+
+impl ProposalAction {
+    /// Role the proposer must hold, the same one the direct call needs.
+    pub fn required_role(&self) -> Role {
+        match self {
+            ProposalAction::AddAdmin { .. }
+            | ProposalAction::RemoveAdmin { .. }
+            | ProposalAction::GrantRole { .. }
+            | ProposalAction::RevokeRole { .. }
+            | ProposalAction::ProposeOwner { .. }
+            | ProposalAction::RenounceOwnership
+            | ProposalAction::SetGovernance { .. }
+            | ProposalAction::SetMigrationMode { .. }
+            | ProposalAction::Unpause { .. }
+            | ProposalAction::LiftEmergencyStop => Role::Owner,
+            ProposalAction::AddVerifierKey { .. }
+            | ProposalAction::SetVerifierKeyExpiry { .. }
+            | ProposalAction::RevokeVerifierKey { .. } => Role::VerifierManager,
+            ProposalAction::Mint { .. } | ProposalAction::SetEpochBudget { .. } => Role::Minter,
+            ProposalAction::SetPrice { .. } => Role::PriceManager,
+        }
+    }
+
+    /// Role every approver must hold. `Owner` has a single holder, so owner
+    /// actions are approved by any admin instead.
+    pub fn approver_role(&self) -> Option<Role> {
+        match self.required_role() {
+            Role::Owner => None,
+            role => Some(role),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Proposal {
+    pub id: u32,
+    pub action: ProposalAction,
+    pub proposer: ActorId,
+    pub approvals: Vec<ActorId>,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub executed: bool,
+}
+
+impl Proposal {
+    pub fn is_pending(&self, now: u64) -> bool {
+        !self.executed && now < self.expires_at
+    }
+}
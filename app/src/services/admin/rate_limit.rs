@@ -0,0 +1,58 @@
+use sails_rs::{collections::HashMap, prelude::*};
+
+/// Entry points that can be throttled per actor.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode, TypeInfo)]
+pub enum RateLimitedAction {
+    CreateGame,
+    Buy,
+}
+
+/// At most `max_calls` calls per actor in every window of `window` ms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub struct RateLimit {
+    pub max_calls: u32,
+    pub window: u64,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Usage {
+    window_start: u64,
+    calls: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct RateLimits {
+    pub limits: HashMap<RateLimitedAction, RateLimit>,
+    usage: HashMap<(ActorId, RateLimitedAction), Usage>,
+}
+
+impl RateLimits {
+    /// Counts a call by `actor`, or returns the timestamp from which it may
+    /// call again. Actions without a limit are never throttled.
+    pub fn hit(&mut self, actor: ActorId, action: RateLimitedAction, now: u64) -> Result<(), u64> {
+        let Some(limit) = self.limits.get(&action).copied() else {
+            return Ok(());
+        };
+        let usage = self.usage.entry((actor, action)).or_insert(Usage {
+            window_start: now,
+            calls: 0,
+        });
+        // Only the caller's own counter is touched, an ended window restarts
+        if now >= usage.window_start.saturating_add(limit.window) {
+            *usage = Usage {
+                window_start: now,
+                calls: 0,
+            };
+        }
+        if usage.calls >= limit.max_calls {
+            return Err(usage.window_start.saturating_add(limit.window));
+        }
+        usage.calls += 1;
+        Ok(())
+    }
+
+    /// Drops the counters of `action` so a new limit starts from a clean slate.
+    pub fn reset(&mut self, action: RateLimitedAction) {
+        self.usage.retain(|(_, counted), _| *counted != action);
+    }
+}
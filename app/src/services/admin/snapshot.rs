@@ -0,0 +1,111 @@
+use super::{AdminError, Admins, Ban, PauseTarget, RateLimit, RateLimitedAction, Role};
+use crate::services::utils::{page, sorted_keys};
+use sails_rs::prelude::*;
+
+/// One piece of the admin storage. Proposals and the audit log are history
+/// of the old instance and are not carried over.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum AdminRecord {
+    /// Exported after the roles, so the threshold is checked against the
+    /// admins already imported.
+    Governance {
+        approval_threshold: u32,
+        proposal_lifetime: u64,
+        large_mint_limit: U256,
+    },
+    PendingOwner(ActorId),
+    Paused(PauseTarget),
+    EmergencyStop(bool),
+    BansBlockTransfers(bool),
+    /// `Owner` is skipped on import, the new instance keeps its deployer as owner.
+    Roles {
+        account: ActorId,
+        roles: Vec<Role>,
+    },
+    Ban {
+        account: ActorId,
+        ban: Ban,
+    },
+    RateLimit {
+        action: RateLimitedAction,
+        limit: RateLimit,
+    },
+}
+
+pub fn export(offset: u32, limit: u32) -> Vec<AdminRecord> {
+    let admins = Admins::get();
+    let mut paused: Vec<PauseTarget> = admins.paused.iter().copied().collect();
+    paused.sort();
+    let mut rate_limits: Vec<(RateLimitedAction, RateLimit)> = admins
+        .rate_limits
+        .limits
+        .iter()
+        .map(|(action, limit)| (*action, *limit))
+        .collect();
+    rate_limits.sort();
+    let settings = [
+        AdminRecord::EmergencyStop(admins.emergency_stop),
+        AdminRecord::BansBlockTransfers(admins.bans_block_transfers),
+    ];
+    let records = settings
+        .into_iter()
+        .chain(admins.pending_owner.map(AdminRecord::PendingOwner))
+        .chain(paused.into_iter().map(AdminRecord::Paused))
+        .chain(sorted_keys(&admins.roles).into_iter().map(|account| AdminRecord::Roles {
+            account,
+            roles: admins.roles[&account].clone(),
+        }))
+        .chain(sorted_keys(&admins.bans).into_iter().map(|account| AdminRecord::Ban {
+            account,
+            ban: admins.bans[&account].clone(),
+        }))
+        .chain(
+            rate_limits
+                .into_iter()
+                .map(|(action, limit)| AdminRecord::RateLimit { action, limit }),
+        )
+        .chain([AdminRecord::Governance {
+            approval_threshold: admins.approval_threshold,
+            proposal_lifetime: admins.proposal_lifetime,
+            large_mint_limit: admins.large_mint_limit,
+        }]);
+    page(records, offset, limit)
+}
+
+/// A threshold the imported admins can't reach is refused, it would keep the
+/// new instance in migration mode for good.
+pub fn import(records: Vec<AdminRecord>) -> Result<(), AdminError> {
+    let admins = Admins::get_mut();
+    for record in records {
+        match record {
+            AdminRecord::Governance {
+                approval_threshold,
+                proposal_lifetime,
+                large_mint_limit,
+            } => {
+                admins.check_governance(approval_threshold)?;
+                admins.approval_threshold = approval_threshold;
+                admins.proposal_lifetime = proposal_lifetime;
+                admins.large_mint_limit = large_mint_limit;
+            }
+            AdminRecord::PendingOwner(account) => admins.pending_owner = Some(account),
+            AdminRecord::Paused(target) => {
+                admins.paused.insert(target);
+            }
+            AdminRecord::EmergencyStop(active) => admins.emergency_stop = active,
+            AdminRecord::BansBlockTransfers(active) => admins.bans_block_transfers = active,
+            AdminRecord::Roles { account, roles } => {
+                for role in roles.into_iter().filter(|role| *role != Role::Owner) {
+                    admins.grant(account, role);
+                }
+            }
+            AdminRecord::Ban { account, ban } => {
+                admins.bans.insert(account, ban);
+            }
+            AdminRecord::RateLimit { action, limit } => {
+                admins.rate_limits.limits.insert(action, limit);
+            }
+        }
+    }
+    Ok(())
+}
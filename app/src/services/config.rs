@@ -0,0 +1,71 @@
+use crate::services::{game::GameSettings, item_vmt::utils::TokenMetadata};
+use sails_rs::{hex, prelude::*};
+use vmt_service::utils::TokenId;
+
+/// Verifier key registered under id 0 by the default preset.
+const DEFAULT_VERIFIER_KEY: &str = "b8c4cd5e14f7ae7cab1b9d1ce101648f96295a4805abc95bdf907740f8985220";
+
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct TokenConfig {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+/// GOLD supply limits.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct EmissionConfig {
+    /// Hard cap on the total supply, it can't be changed later.
+    pub max_supply: U256,
+    /// GOLD that game rewards may mint per day.
+    pub epoch_budget: U256,
+}
+
+/// Everything the constructor used to hardcode, so each network can be
+/// deployed with its own values.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug)]
+pub struct InitConfig {
+    /// Granted every admin role. The deployer always becomes the owner.
+    pub admins: Vec<ActorId>,
+    pub settings: GameSettings,
+    /// Registered as active from deployment, without an expiry.
+    pub verifier_keys: Vec<(u32, [u8; 32])>,
+    pub gold: TokenConfig,
+    pub emission: EmissionConfig,
+    /// Item catalog, metadata keyed by token id.
+    pub items: Vec<(TokenId, TokenMetadata)>,
+    /// Initial market prices in GOLD.
+    pub prices: Vec<(TokenId, U256)>,
+}
+
+impl Default for InitConfig {
+    fn default() -> Self {
+        let public_key: [u8; 32] = hex::decode(DEFAULT_VERIFIER_KEY)
+            .expect("Decoding failed")
+            .try_into()
+            .expect("Invalid public key");
+        let item = |title: &str| TokenMetadata {
+            title: Some(title.to_string()),
+            description: Some(title.to_string()),
+            media: None,
+            reference: None,
+        };
+        Self {
+            admins: Vec::new(),
+            settings: GameSettings::preset(),
+            verifier_keys: vec![(0, public_key)],
+            gold: TokenConfig {
+                name: "Game Gold".to_owned(),
+                symbol: "GOLD".to_owned(),
+                decimals: 2,
+            },
+            // 1 billion GOLD, at most 1 million of it paid out as rewards per day
+            emission: EmissionConfig {
+                max_supply: 100_000_000_000u64.into(),
+                epoch_budget: 100_000_000.into(),
+            },
+            items: vec![(110.into(), item("Candy")), (220.into(), item("Hummer"))],
+            prices: vec![(110.into(), 100.into()), (220.into(), 200.into())],
+        }
+    }
+}
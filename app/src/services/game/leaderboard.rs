@@ -0,0 +1,91 @@
+use sails_rs::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+    player: ActorId,
+    score: i32,
+    reached_at: u64,
+}
+
+impl Entry {
+    // Higher score first, ties going to whoever reached the score earlier
+    fn ranks_before(&self, score: i32, reached_at: u64) -> bool {
+        self.score > score || (self.score == score && self.reached_at <= reached_at)
+    }
+}
+
+/// Top-N scores kept sorted as they come in, so queries never sort.
+#[derive(Default)]
+pub struct Leaderboard {
+    entries: Vec<Entry>,
+    capacity: u32,
+}
+
+impl Leaderboard {
+    pub fn new(capacity: u32) -> Self {
+        Self {
+            entries: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Records `score` for `player` unless they already hold a better or equal
+    /// one, or it doesn't make the top `capacity`.
+    pub fn submit(&mut self, player: ActorId, score: i32, reached_at: u64) {
+        if let Some(pos) = self.entries.iter().position(|entry| entry.player == player) {
+            if self.entries[pos].score >= score {
+                return;
+            }
+            self.entries.remove(pos);
+        }
+
+        let pos = self
+            .entries
+            .partition_point(|entry| entry.ranks_before(score, reached_at));
+        if pos >= self.capacity as usize {
+            return;
+        }
+        self.entries.insert(
+            pos,
+            Entry {
+                player,
+                score,
+                reached_at,
+            },
+        );
+        self.entries.truncate(self.capacity as usize);
+    }
+
+    pub fn set_capacity(&mut self, capacity: u32) {
+        self.capacity = capacity;
+        self.entries.truncate(capacity as usize);
+    }
+
+    pub fn page(&self, offset: u32, limit: u32) -> Vec<(ActorId, i32)> {
+        self.entries
+            .iter()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|entry| (entry.player, entry.score))
+            .collect()
+    }
+
+    /// 1-based rank of `player`, if they are on the board.
+    pub fn rank(&self, player: &ActorId) -> Option<u32> {
+        self.entries
+            .iter()
+            .position(|entry| entry.player == *player)
+            .map(|pos| pos as u32 + 1)
+    }
+
+    /// `(player, score, reached_at)` from best to worst.
+    pub fn entries(&self) -> impl Iterator<Item = (ActorId, i32, u64)> + '_ {
+        self.entries
+            .iter()
+            .map(|entry| (entry.player, entry.score, entry.reached_at))
+    }
+
+    pub fn len(&self) -> u32 {
+        self.entries.len() as u32
+    }
+}
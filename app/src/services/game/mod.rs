@@ -1,16 +1,38 @@
-use crate::admin::Admins;
-use crate::services::{gold_vft::GoldService, item_vmt::ItemService};
+use crate::admin::{
+    self, audit::AdminActionKind, rate_limit::RateLimitedAction, Admins, PauseTarget, Role,
+};
+use crate::services::{
+    self,
+    gold_vft::{accounting::GoldSource, GoldService},
+    item_vmt::ItemService,
+};
 use gstd::{exec, msg};
-use sails_rs::hex;
-use sails_rs::{collections::HashMap, gstd::service, prelude::*};
+use sails_rs::{
+    collections::{HashMap, HashSet},
+    gstd::service,
+    prelude::*,
+};
 use schnorrkel::{PublicKey, Signature};
 use vmt_service::utils::TokenId;
+mod leaderboard;
+pub mod season;
+pub mod session_key;
+pub mod settings;
+pub mod snapshot;
+use leaderboard::Leaderboard;
+use season::*;
+use session_key::*;
+use settings::*;
 
 #[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
 pub enum GameStatus {
     Created,
     InProgress,
     Ended,
+    /// Created but never started before its time ran out.
+    Abandoned,
+    /// Started but not settled before its time ran out.
+    Expired,
 }
 
 impl Default for GameStatus {
@@ -26,15 +48,92 @@ pub struct GameInfo {
     status: GameStatus,
     score: i32,
     creator: ActorId,
+    created_at: u64,
+    started_at: Option<u64>,
+    // Reward reached at the last checkpoint, paid out if the run expires
+    partial_earn: U256,
+}
+
+impl GameInfo {
+    /// Timestamp until which the game can be started or, once started, played.
+    /// `time` is in seconds while block timestamps are in milliseconds.
+    fn deadline(&self) -> u64 {
+        let game_time = u64::from(self.time) * 1000;
+        self.started_at.unwrap_or(self.created_at) + game_time
+    }
+
+    fn is_open(&self) -> bool {
+        matches!(self.status, GameStatus::Created | GameStatus::InProgress)
+    }
+
+    fn ensure_in_progress(&self, now: u64, grace_period: u64) -> Result<(), GameError> {
+        match self.status {
+            GameStatus::InProgress => {}
+            GameStatus::Created => return Err(GameError::GameNotStarted),
+            GameStatus::Ended => return Err(GameError::GameAlreadySettled),
+            _ => return Err(GameError::GameClosed),
+        }
+        if now > self.deadline() + grace_period {
+            return Err(GameError::GameExpired);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Encode, Decode, TypeInfo, Clone, Debug, Default)]
 pub struct GameSettings {
-    verifier_public_key: Option<Vec<u8>>,
     game_time: u32,
     max_earn: u32,
     initial_max_stamina: u64,
     stamina_recovery_rate: u64,
+    // Extra time (ms) after the deadline for the verifier's result to land
+    grace_period: u64,
+    // Number of players kept on the all-time and season leaderboards
+    leaderboard_size: u32,
+    // Gas attached to the delayed expiry message, 0 disables scheduling
+    expiry_gas_limit: u64,
+    refund_stamina_on_expiry: bool,
+    // Minimum notice (ms) before a queued settings change can take effect
+    setting_change_delay: u64,
+    // Percentage of each `update_game` reward that is locked in vesting
+    vested_reward_share: u8,
+    // Time (ms) over which a locked reward unlocks linearly
+    vesting_period: u64,
+}
+
+impl GameSettings {
+    /// Values of the default deployment preset.
+    pub fn preset() -> Self {
+        GameSettings {
+            game_time: 60,
+            max_earn: 2000,
+            initial_max_stamina: 5,
+            stamina_recovery_rate: 1800000,
+            grace_period: 30000,
+            leaderboard_size: DEFAULT_LEADERBOARD_SIZE,
+            expiry_gas_limit: 10_000_000_000,
+            refund_stamina_on_expiry: false,
+            setting_change_delay: 0,
+            vested_reward_share: 0,
+            vesting_period: 7 * 24 * 60 * 60 * 1000,
+        }
+    }
+}
+
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct VerifierKey {
+    public_key: [u8; 32],
+    activated_at: u64,
+    expires_at: Option<u64>,
+    revoked: bool,
+}
+
+impl VerifierKey {
+    fn is_active(&self, now: u64) -> bool {
+        !self.revoked
+            && self.activated_at <= now
+            && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
 }
 
 #[derive(Encode, Decode, TypeInfo, Clone, Debug, Default)]
@@ -49,11 +148,139 @@ pub struct Player {
     min_stamina_block: u64,
 }
 
+/// Current layout of [`GameResult`]; bumped whenever a field is added or changed.
+pub const GAME_RESULT_VERSION: u8 = 2;
+
+/// Current layout of [`GameCheckpoint`].
+pub const GAME_CHECKPOINT_VERSION: u8 = 1;
+
+/// Prefix of the schnorrkel signing context for game results. The program id is
+/// appended to it, so a signature is only valid for one deployment.
+pub const GAME_RESULT_CONTEXT: &[u8] = b"animal-rogue/game-result";
+
+/// Prefix of the signing context for mid-run checkpoints.
+pub const GAME_CHECKPOINT_CONTEXT: &[u8] = b"animal-rogue/game-checkpoint";
+
+/// Outcome of a run as signed by the off-chain verifier. The signed message is
+/// the SCALE encoding of this struct.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct GameResult {
+    version: u8,
+    game_id: u32,
+    creator: ActorId,
+    stage: u32,
+    score: i32,
+    earn: U256,
+    token_ids: Vec<TokenId>,
+    amounts: Vec<U256>,
+    deadline: u64,
+}
+
+/// Progress of a run at the end of a stage, signed by the off-chain verifier.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct GameCheckpoint {
+    version: u8,
+    game_id: u32,
+    creator: ActorId,
+    stage: u32,
+    partial_score: i32,
+    partial_earn: U256,
+    deadline: u64,
+}
+
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct GameSettled {
+    game_id: u32,
+    creator: ActorId,
+    score: i32,
+    // GOLD actually minted, after `max_earn` and the epoch budget
+    earn: U256,
+    token_ids: Vec<TokenId>,
+    amounts: Vec<U256>,
+    settled_at: u64,
+}
+
 #[derive(Default)]
 pub struct GameStorage {
     games: HashMap<u32, GameInfo>,
+    settlements: HashMap<u32, GameSettled>,
+    open_games: HashMap<ActorId, Vec<u32>>,
+    last_game_id: u32,
+    verifier_keys: HashMap<u32, VerifierKey>,
+    seasons: HashMap<u32, Season>,
+    last_season_id: u32,
+    // Season boards stop changing once the season is closed
+    season_leaderboards: HashMap<u32, Leaderboard>,
+    // Index into the frozen standings the next reward batch starts at
+    season_payout_cursor: HashMap<u32, u32>,
+    season_paid: HashSet<(u32, ActorId)>,
     settings: GameSettings,
+    // Ordered by activation time
+    pending_setting_changes: Vec<PendingSettingChange>,
+    last_setting_change_id: u32,
     players: HashMap<ActorId, Player>,
+    // Registration order, for stable pagination
+    player_ids: Vec<ActorId>,
+    leaderboard: Leaderboard,
+    // Keyed by the session key's own account
+    session_keys: HashMap<ActorId, SessionKey>,
+}
+
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameError {
+    GameNotFound,
+    GameAlreadySettled,
+    GameNotStarted,
+    GameClosed,
+    GameExpired,
+    GameNotExpired,
+    InvalidGameStatus,
+    NotGameCreator,
+    VerifierKeyNotFound,
+    VerifierKeyExists,
+    VerifierKeyInactive,
+    InvalidKeyPeriod,
+    InvalidPublicKey,
+    ProposalRequired,
+    InvalidSignature,
+    UnsupportedVersion,
+    CreatorMismatch,
+    SignatureExpired,
+    StageNotIncreasing,
+    InconsistentWithCheckpoint,
+    SeasonNotFound,
+    SeasonClosed,
+    InvalidSeasonPeriod,
+    SeasonOverlap,
+    InvalidRewardTier,
+    SeasonNotClosed,
+    RewardsDistributionStarted,
+    RewardsAlreadyDistributed,
+    /// A payout batch has to go through at least one player.
+    InvalidBatchSize,
+    LengthMismatch,
+    PlayerNotFound,
+    Paused,
+    Banned,
+    /// Too many calls in the current window, retry from `retry_after` (ms).
+    RateLimited {
+        retry_after: u64,
+    },
+    MissingRole,
+    NotEnoughStamina,
+    NotEnoughItems,
+    SettingChangeNotFound,
+    ActivationTooEarly,
+    /// The new value is out of range for its setting.
+    InvalidSetting,
+    /// The calling session key may not perform this action.
+    SessionActionNotAllowed,
+    /// A key can't be its owner's main account or another session key.
+    InvalidSessionKey,
+    InvalidSessionExpiry,
+    /// The key already acts for another player.
+    SessionKeyTaken,
+    SessionKeyNotFound,
 }
 
 #[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
@@ -62,32 +289,127 @@ pub enum GameEvent {
         game_id: u32,
         creator: ActorId,
     },
+    GameStarted {
+        game_id: u32,
+        started_at: u64,
+    },
+    GameClosed {
+        game_id: u32,
+        status: GameStatus,
+    },
     GameUpdated {
         game_id: u32,
         score: i32,
         earn: U256,
     },
+    StageReached {
+        game_id: u32,
+        stage: u32,
+        partial_score: i32,
+    },
+    VerifierKeyAdded {
+        key_id: u32,
+        public_key: [u8; 32],
+        activated_at: u64,
+        expires_at: Option<u64>,
+    },
+    VerifierKeyExpiryUpdated {
+        key_id: u32,
+        expires_at: Option<u64>,
+    },
+    VerifierKeyRevoked {
+        key_id: u32,
+    },
+    SeasonCreated {
+        season_id: u32,
+        start: u64,
+        end: u64,
+    },
+    SeasonClosed {
+        season_id: u32,
+    },
+    SeasonRewardsSet {
+        season_id: u32,
+    },
+    SeasonRewardPaid {
+        season_id: u32,
+        rank: u32,
+        player: ActorId,
+    },
+    SettingChangeQueued {
+        change_id: u32,
+        change: SettingChange,
+        activates_at: u64,
+    },
+    SettingChangeApplied {
+        change_id: u32,
+        change: SettingChange,
+    },
+    SettingChangeCancelled {
+        change_id: u32,
+    },
+    SessionKeyRegistered {
+        owner: ActorId,
+        key: ActorId,
+        expires_at: u64,
+        allowed_actions: Vec<SessionAction>,
+    },
+    SessionKeyRevoked {
+        owner: ActorId,
+        key: ActorId,
+    },
 }
 
 static mut GAME_STORAGE: Option<GameStorage> = None;
 
+/// Vara produces a block every 3 seconds.
+const BLOCK_TIME_MS: u64 = 3_000;
+
+/// Most season rewards paid by a single `distribute_season_rewards` call.
+const MAX_REWARD_BATCH: u32 = 50;
+
+const DEFAULT_LEADERBOARD_SIZE: u32 = 100;
+
+/// Most entries returned by a single paginated query.
+const MAX_PAGE_SIZE: u32 = 100;
+
 #[derive(Clone)]
 pub struct GameService {}
 
 impl GameService {
-    pub fn seed() -> Self {
+    pub fn seed(settings: GameSettings, keys: Vec<(u32, [u8; 32])>) -> Self {
+        let verifier_keys = keys
+            .into_iter()
+            .map(|(key_id, public_key)| {
+                let key = VerifierKey {
+                    public_key: PublicKey::from_bytes(&public_key).expect("Invalid public key").to_bytes(),
+                    activated_at: 0,
+                    expires_at: None,
+                    revoked: false,
+                };
+                (key_id, key)
+            })
+            .collect();
+        let leaderboard_size = settings.leaderboard_size;
         unsafe {
-        
             GAME_STORAGE = Some(GameStorage {
                 games: HashMap::new(),
-                settings:     GameSettings {
-                    verifier_public_key: Some(hex::decode("b8c4cd5e14f7ae7cab1b9d1ce101648f96295a4805abc95bdf907740f8985220").expect("Decoding failed")),
-                    game_time: 60,
-                    max_earn: 2000,
-                    initial_max_stamina: 5,
-                    stamina_recovery_rate: 1800000,
-                },
+                settlements: HashMap::new(),
+                open_games: HashMap::new(),
+                last_game_id: 0,
+                verifier_keys,
+                seasons: HashMap::new(),
+                last_season_id: 0,
+                season_leaderboards: HashMap::new(),
+                season_payout_cursor: HashMap::new(),
+                season_paid: HashSet::new(),
+                settings,
+                pending_setting_changes: Vec::new(),
+                last_setting_change_id: 0,
                 players: HashMap::new(),
+                player_ids: Vec::new(),
+                leaderboard: Leaderboard::new(leaderboard_size),
+                session_keys: HashMap::new(),
             });
         };
         GameService {}
@@ -121,50 +443,224 @@ impl GameService {
         storage.games.get(&game_id).cloned()
     }
 
-    pub fn set_verifier_public_key(&mut self, public_key: Vec<u8>) {
-        self.ensure_is_admin();
-        let storage = self.get_mut();
-        storage.settings.verifier_public_key = Some(public_key);
+    /// Registers a verifier key under `key_id`. Several keys may be active at
+    /// once, so a new key can be rolled out before the old one expires.
+    pub fn add_verifier_key(
+        &mut self,
+        key_id: u32,
+        public_key: Vec<u8>,
+        activated_at: u64,
+        expires_at: Option<u64>,
+    ) -> Result<(), GameError> {
+        self.check_role(Role::VerifierManager)?;
+        if Admins::requires_proposal() {
+            return Err(GameError::ProposalRequired);
+        }
+        let params = (key_id, public_key.clone(), activated_at, expires_at);
+        self.add_verifier_key_internal(key_id, public_key, activated_at, expires_at)?;
+        admin::record_admin_action(AdminActionKind::VerifierKeyAdded, &params);
+        Ok(())
     }
 
-    pub fn set_game_time(&mut self, game_time: u32) {
-        self.ensure_is_admin();
-        let storage = self.get_mut();
-        storage.settings.game_time = game_time;
+    pub fn set_verifier_key_expiry(
+        &mut self,
+        key_id: u32,
+        expires_at: Option<u64>,
+    ) -> Result<(), GameError> {
+        self.check_role(Role::VerifierManager)?;
+        if Admins::requires_proposal() {
+            return Err(GameError::ProposalRequired);
+        }
+        self.set_verifier_key_expiry_internal(key_id, expires_at)?;
+        admin::record_admin_action(AdminActionKind::VerifierKeyExpiryUpdated, &(key_id, expires_at));
+        Ok(())
     }
 
-    pub fn set_max_earn(&mut self, max_earn: u32) {
-        self.ensure_is_admin();
-        let storage = self.get_mut();
-        storage.settings.max_earn = max_earn;
+    pub fn revoke_verifier_key(&mut self, key_id: u32) -> Result<(), GameError> {
+        self.check_role(Role::VerifierManager)?;
+        if Admins::requires_proposal() {
+            return Err(GameError::ProposalRequired);
+        }
+        self.revoke_verifier_key_internal(key_id)?;
+        admin::record_admin_action(AdminActionKind::VerifierKeyRevoked, &key_id);
+        Ok(())
     }
 
-    pub fn set_initial_max_stamina(&mut self, initial_max_stamina: u64) {
-        self.ensure_is_admin();
-        let storage = self.get_mut();
-        storage.settings.initial_max_stamina = initial_max_stamina;
+    pub fn get_verifier_keys(&self) -> Vec<(u32, VerifierKey)> {
+        let storage = self.get();
+        storage
+            .verifier_keys
+            .iter()
+            .map(|(id, key)| (*id, key.clone()))
+            .collect()
+    }
+
+    // The setters below queue their change with the minimum delay, so they
+    // only apply immediately while `setting_change_delay` is 0.
+    pub fn set_game_time(&mut self, game_time: u32) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(SettingChange::GameTime(game_time), None);
+        Ok(())
+    }
+
+    pub fn set_max_earn(&mut self, max_earn: u32) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(SettingChange::MaxEarn(max_earn), None);
+        Ok(())
+    }
+
+    pub fn set_initial_max_stamina(&mut self, initial_max_stamina: u64) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(
+            SettingChange::InitialMaxStamina(initial_max_stamina),
+            None,
+        );
+        Ok(())
+    }
+
+    pub fn set_stamina_recovery_rate(
+        &mut self,
+        stamina_recovery_rate: u64,
+    ) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        let change = SettingChange::StaminaRecoveryRate(stamina_recovery_rate);
+        if !change.is_valid() {
+            return Err(GameError::InvalidSetting);
+        }
+        self.queue_setting_change_internal(change, None);
+        Ok(())
+    }
+
+    pub fn set_grace_period(&mut self, grace_period: u64) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(SettingChange::GracePeriod(grace_period), None);
+        Ok(())
+    }
+
+    pub fn set_leaderboard_size(&mut self, leaderboard_size: u32) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(SettingChange::LeaderboardSize(leaderboard_size), None);
+        Ok(())
+    }
+
+    pub fn set_expiry_gas_limit(&mut self, expiry_gas_limit: u64) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(SettingChange::ExpiryGasLimit(expiry_gas_limit), None);
+        Ok(())
+    }
+
+    pub fn set_refund_stamina_on_expiry(
+        &mut self,
+        refund_stamina_on_expiry: bool,
+    ) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(
+            SettingChange::RefundStaminaOnExpiry(refund_stamina_on_expiry),
+            None,
+        );
+        Ok(())
+    }
+
+    /// Percentage (0-100) of each `update_game` reward locked in vesting.
+    pub fn set_vested_reward_share(&mut self, vested_reward_share: u8) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        let change = SettingChange::VestedRewardShare(vested_reward_share);
+        if !change.is_valid() {
+            return Err(GameError::InvalidSetting);
+        }
+        self.queue_setting_change_internal(change, None);
+        Ok(())
+    }
+
+    pub fn set_vesting_period(&mut self, vesting_period: u64) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.queue_setting_change_internal(SettingChange::VestingPeriod(vesting_period), None);
+        Ok(())
+    }
+
+    /// Schedules `change` for `activates_at`, which must leave at least
+    /// `setting_change_delay` of notice. Returns the change id.
+    pub fn queue_setting_change(
+        &mut self,
+        change: SettingChange,
+        activates_at: u64,
+    ) -> Result<u32, GameError> {
+        self.check_role(Role::GameOperator)?;
+        if !change.is_valid() {
+            return Err(GameError::InvalidSetting);
+        }
+        let storage = self.get();
+        if activates_at < exec::block_timestamp() + storage.settings.setting_change_delay {
+            return Err(GameError::ActivationTooEarly);
+        }
+        Ok(self.queue_setting_change_internal(change, Some(activates_at)))
     }
 
-    pub fn set_stamina_recovery_rate(&mut self, stamina_recovery_rate: u64) {
-        self.ensure_is_admin();
+    /// Drops a queued change that has not taken effect yet.
+    pub fn cancel_setting_change(&mut self, change_id: u32) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        self.apply_due_setting_changes();
         let storage = self.get_mut();
-        storage.settings.stamina_recovery_rate = stamina_recovery_rate;
+        let position = storage
+            .pending_setting_changes
+            .iter()
+            .position(|pending| pending.id == change_id)
+            .ok_or(GameError::SettingChangeNotFound)?;
+        storage.pending_setting_changes.remove(position);
+        self.notify_on(GameEvent::SettingChangeCancelled { change_id })
+            .expect("Notification Error");
+        admin::record_admin_action(AdminActionKind::SettingChangeCancelled, &change_id);
+        Ok(())
+    }
+
+    /// Applies every queued change whose activation time has passed. Anyone
+    /// can call it; game calls do the same on their own. Returns how many
+    /// changes were applied.
+    pub fn apply_setting_changes(&mut self) -> u32 {
+        self.apply_due_setting_changes()
     }
 
+    /// Settings in effect now, including changes that are due but were not
+    /// applied by a call yet.
     pub fn get_settings(&self) -> GameSettings {
         let storage = self.get();
-        storage.settings.clone()
+        let now = exec::block_timestamp();
+        let mut settings = storage.settings.clone();
+        storage
+            .pending_setting_changes
+            .iter()
+            .take_while(|pending| pending.activates_at <= now)
+            .for_each(|pending| pending.change.apply(&mut settings));
+        settings
     }
 
-    pub fn create_game(&mut self) -> u32 {
+    /// Changes that are queued but not yet in effect, in activation order.
+    pub fn get_pending_setting_changes(&self) -> Vec<PendingSettingChange> {
+        let now = exec::block_timestamp();
+        self.get()
+            .pending_setting_changes
+            .iter()
+            .filter(|pending| pending.activates_at > now)
+            .cloned()
+            .collect()
+    }
+
+    pub fn create_game(&mut self) -> Result<u32, GameError> {
+        if Admins::is_paused(PauseTarget::Game) {
+            return Err(GameError::Paused);
+        }
+        let player_id = self.acting_player(SessionAction::CreateGame)?;
+        if Admins::is_banned(&player_id) {
+            return Err(GameError::Banned);
+        }
+        self.apply_due_setting_changes();
         let storage = self.get_mut();
-        let player_id = msg::source();
 
         // Check if the player exists
         let player = storage
             .players
             .get_mut(&player_id)
-            .expect("Player not registered");
+            .ok_or(GameError::PlayerNotFound)?;
 
         // Calculate the current block height
         let current_block = exec::block_timestamp();
@@ -189,99 +685,578 @@ impl GameService {
 
         // Check if the player has enough stamina
         if player.stamina == 0 {
-            panic!("Not enough stamina to start a game");
+            return Err(GameError::NotEnoughStamina);
         }
+        // Only calls that would otherwise succeed count against the limit
+        Admins::check_rate_limit(player_id, RateLimitedAction::CreateGame)
+            .map_err(|retry_after| GameError::RateLimited { retry_after })?;
         // Deduct 1 stamina
         player.stamina -= 1;
 
-        let game_id = storage.games.len() as u32 + 1;
+        // Ids are never reused, so a signature for one game can't settle another
+        storage.last_game_id += 1;
+        let game_id = storage.last_game_id;
         let game = GameInfo {
             stage: 0,
             time: storage.settings.game_time,
             status: GameStatus::Created,
             score: 0,
             creator: player_id,
+            created_at: current_block,
+            started_at: None,
+            partial_earn: U256::zero(),
         };
         storage.games.insert(game_id, game);
+        storage.open_games.entry(player_id).or_default().push(game_id);
+
+        // Make sure the session gets closed even if nobody settles or expires it
+        if storage.settings.expiry_gas_limit > 0 {
+            let game_time = u64::from(storage.settings.game_time) * 1000;
+            // Worst case the game is started right before its start deadline
+            let expires_in = 2 * game_time + storage.settings.grace_period;
+            let delay = (expires_in / BLOCK_TIME_MS + 1) as u32;
+            let payload = ["Game".encode(), "ExpireGame".encode(), game_id.encode()].concat();
+            msg::send_bytes_with_gas_delayed(
+                exec::program_id(),
+                payload,
+                storage.settings.expiry_gas_limit,
+                0,
+                delay,
+            )
+            .expect("Failed to schedule game expiry");
+        }
 
         // Emit GameCreated event
         self.notify_on(GameEvent::GameCreated { game_id, creator: player_id })
         .expect("Notification Error");
 
 
-        game_id
+        Ok(game_id)
+    }
+
+    pub fn start_game(&mut self, game_id: u32) -> Result<(), GameError> {
+        if Admins::is_paused(PauseTarget::Game) {
+            return Err(GameError::Paused);
+        }
+        self.apply_due_setting_changes();
+        let storage = self.get_mut();
+        let caller = self.acting_player(SessionAction::CreateGame)?;
+        if Admins::is_banned(&caller) {
+            return Err(GameError::Banned);
+        }
+        let game = storage.games.get_mut(&game_id).ok_or(GameError::GameNotFound)?;
+        if game.creator != caller {
+            return Err(GameError::NotGameCreator);
+        }
+        if game.status != GameStatus::Created {
+            return Err(GameError::InvalidGameStatus);
+        }
+        let now = exec::block_timestamp();
+        if now > game.deadline() {
+            return Err(GameError::GameExpired);
+        }
+
+        game.started_at = Some(now);
+        game.status = GameStatus::InProgress;
+
+        self.notify_on(GameEvent::GameStarted { game_id, started_at: now })
+            .expect("Notification Error");
+        Ok(())
+    }
+
+    /// Closes a game whose time and grace period have run out without a
+    /// settlement. Anyone may call it, and `create_game` schedules a call to it
+    /// from the program itself. Only the reward of the last checkpoint is paid,
+    /// and nothing to a banned creator. Refused while games are paused, so the
+    /// checkpoint is still paid once they resume.
+    pub fn expire_game(&mut self, game_id: u32) -> Result<(), GameError> {
+        if Admins::is_paused(PauseTarget::Game) {
+            return Err(GameError::Paused);
+        }
+        self.apply_due_setting_changes();
+        let storage = self.get_mut();
+        let game = storage.games.get_mut(&game_id).ok_or(GameError::GameNotFound)?;
+        if !game.is_open() {
+            return Err(GameError::GameClosed);
+        }
+        if exec::block_timestamp() <= game.deadline() + storage.settings.grace_period {
+            return Err(GameError::GameNotExpired);
+        }
+
+        game.status = match game.status {
+            GameStatus::Created => GameStatus::Abandoned,
+            _ => GameStatus::Expired,
+        };
+        let status = game.status.clone();
+        close_session(&mut storage.open_games, game.creator, game_id);
+
+        if storage.settings.refund_stamina_on_expiry {
+            if let Some(player) = storage.players.get_mut(&game.creator) {
+                player.stamina = (player.stamina + 1).min(player.max_stamina);
+            }
+        }
+
+        // A crashed run keeps what it had verifiably earned so far
+        if !game.partial_earn.is_zero() && !Admins::is_banned(&game.creator) {
+            let earn = game.partial_earn.min(storage.settings.max_earn.into());
+            let earn = GoldService::new().mint_reward(game.creator, earn);
+            storage.settlements.insert(
+                game_id,
+                GameSettled {
+                    game_id,
+                    creator: game.creator,
+                    score: game.score,
+                    earn,
+                    token_ids: Vec::new(),
+                    amounts: Vec::new(),
+                    settled_at: exec::block_timestamp(),
+                },
+            );
+        }
+
+        self.notify_on(GameEvent::GameClosed { game_id, status })
+            .expect("Notification Error");
+        Ok(())
+    }
+
+    /// Games of `player` that are still waiting to be started or settled.
+    pub fn get_open_games(&self, player: ActorId) -> Vec<(u32, GameInfo)> {
+        let storage = self.get();
+        storage
+            .open_games
+            .get(&player)
+            .map(|game_ids| {
+                game_ids
+                    .iter()
+                    .filter_map(|id| storage.games.get(id).map(|game| (*id, game.clone())))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Records verified progress of a running game.
+    pub fn checkpoint_game(
+        &mut self,
+        checkpoint: GameCheckpoint,
+        key_id: u32,
+        sign: Vec<u8>,
+    ) -> Result<(), GameError> {
+        if Admins::is_paused(PauseTarget::Game) {
+            return Err(GameError::Paused);
+        }
+        self.apply_due_setting_changes();
+        let storage = self.get_mut();
+        let game_id = checkpoint.game_id;
+        let game = storage.games.get_mut(&game_id).ok_or(GameError::GameNotFound)?;
+        if Admins::is_banned(&game.creator) {
+            return Err(GameError::Banned);
+        }
+        let now = exec::block_timestamp();
+        game.ensure_in_progress(now, storage.settings.grace_period)?;
+
+        if checkpoint.version != GAME_CHECKPOINT_VERSION {
+            return Err(GameError::UnsupportedVersion);
+        }
+        if checkpoint.creator != game.creator {
+            return Err(GameError::CreatorMismatch);
+        }
+        if checkpoint.deadline < now {
+            return Err(GameError::SignatureExpired);
+        }
+        if checkpoint.stage <= game.stage {
+            return Err(GameError::StageNotIncreasing);
+        }
+        // Stage 0 means no checkpoint has been recorded yet
+        if game.stage > 0
+            && (checkpoint.partial_score < game.score
+                || checkpoint.partial_earn < game.partial_earn)
+        {
+            return Err(GameError::InconsistentWithCheckpoint);
+        }
+
+        verify_signature(
+            &storage.verifier_keys,
+            key_id,
+            GAME_CHECKPOINT_CONTEXT,
+            &checkpoint.encode(),
+            &sign,
+        )?;
+
+        game.stage = checkpoint.stage;
+        game.score = checkpoint.partial_score;
+        game.partial_earn = checkpoint.partial_earn;
+
+        self.notify_on(GameEvent::StageReached {
+            game_id,
+            stage: checkpoint.stage,
+            partial_score: checkpoint.partial_score,
+        })
+        .expect("Notification Error");
+        Ok(())
     }
 
     pub fn update_game(
         &mut self,
-        game_id: u32,
-        score: i32,
-        earn: U256,
+        result: GameResult,
+        key_id: u32,
         sign: Vec<u8>,
-        token_ids: Vec<TokenId>,
-        amounts: Vec<U256>,
-    ) {
+    ) -> Result<(), GameError> {
+        if Admins::is_paused(PauseTarget::Game) {
+            return Err(GameError::Paused);
+        }
+        self.apply_due_setting_changes();
         let storage = self.get_mut();
-        if let Some(game) = storage.games.get_mut(&game_id) {
-            // if earn > storage.settings.max_earn.into() {
-            //     panic!("Earn exceeds max earn");
-            // }
-            let new_earn = earn.min(storage.settings.max_earn.into());
-
-            // Verify the sign
-            let message = format!("{}{}{}", game_id, score, earn);
-            let public_key_bytes = storage
-                .settings
-                .verifier_public_key
-                .as_ref()
-                .expect("Verifier public key not set");
-            let public_key = PublicKey::from_bytes(public_key_bytes).expect("Invalid public key");
-            let signature = Signature::from_bytes(&sign).expect("Invalid signature");
-            if public_key
-                .verify_simple(b"substrate", message.as_bytes(), &signature)
-                .is_err()
-            {
-                panic!("Invalid signature");
+        let game_id = result.game_id;
+        let game = storage.games.get_mut(&game_id).ok_or(GameError::GameNotFound)?;
+        if Admins::is_banned(&game.creator) {
+            return Err(GameError::Banned);
+        }
+        // Anyone may relay a signed result, but a session key only for its owner
+        if let Some(session_key) = self.active_session_key(&msg::source()) {
+            if !session_key.allows(SessionAction::UpdateGame) {
+                return Err(GameError::SessionActionNotAllowed);
             }
-
-            // Ensure token_ids and amounts have the same length
-            if token_ids.len() != amounts.len() {
-                panic!("Token IDs and amounts length mismatch");
+            if session_key.owner != game.creator {
+                return Err(GameError::NotGameCreator);
             }
+        }
 
-            // Burn items
-            for (token_id, amount) in token_ids.iter().zip(amounts.iter()) {
-                ItemService::burn_internal_notify_off(
-                    ItemService::get_item(),
-                    game.creator,
-                    *token_id,
-                    *amount,
-                );
-            }
+        // A game settles exactly once, whatever signature is presented
+        if storage.settlements.contains_key(&game_id) {
+            return Err(GameError::GameAlreadySettled);
+        }
+        game.ensure_in_progress(exec::block_timestamp(), storage.settings.grace_period)?;
+
+        if result.version != GAME_RESULT_VERSION {
+            return Err(GameError::UnsupportedVersion);
+        }
+        if result.creator != game.creator {
+            return Err(GameError::CreatorMismatch);
+        }
+        if result.deadline < exec::block_timestamp() {
+            return Err(GameError::SignatureExpired);
+        }
 
-            // Earn gold
-            GoldService::mint_internal_notify_off(game.creator, new_earn);
+        // The final result can't fall behind what was already checkpointed
+        if game.stage > 0
+            && (result.stage < game.stage
+                || result.score < game.score
+                || result.earn < game.partial_earn)
+        {
+            return Err(GameError::InconsistentWithCheckpoint);
+        }
 
-            game.score = score;
-            game.status = GameStatus::Ended;
+        // Ensure token_ids and amounts have the same length
+        if result.token_ids.len() != result.amounts.len() {
+            return Err(GameError::LengthMismatch);
+        }
 
-            // Update player's highest score and games played
-            let player = storage
-                .players
-                .get_mut(&game.creator)
-                .expect("Player not found");
-            player.games_played += 1;
-            if score > player.highest_score {
-                player.highest_score = score;
-            }
+        // Verify the sign
+        verify_signature(
+            &storage.verifier_keys,
+            key_id,
+            GAME_RESULT_CONTEXT,
+            &result.encode(),
+            &sign,
+        )?;
+
+        let player = storage
+            .players
+            .get_mut(&game.creator)
+            .ok_or(GameError::PlayerNotFound)?;
+
+        let GameResult {
+            stage,
+            score,
+            earn,
+            token_ids,
+            amounts,
+            ..
+        } = result;
+        let new_earn = earn.min(storage.settings.max_earn.into());
+
+        if token_ids
+            .iter()
+            .zip(amounts.iter())
+            .any(|(token_id, amount)| ItemService::balance_of_mine(game.creator, *token_id) < *amount)
+        {
+            return Err(GameError::NotEnoughItems);
+        }
+
+        // Burn items
+        for (token_id, amount) in token_ids.iter().zip(amounts.iter()) {
+            ItemService::burn_internal_notify_off(
+                ItemService::get_item(),
+                game.creator,
+                *token_id,
+                *amount,
+            );
+        }
+
+        // Earn gold, as much of it as the epoch budget still allows, and lock
+        // the vested share of it
+        let mut gold = GoldService::new();
+        let new_earn = gold.mint_reward(game.creator, new_earn);
+        let vested = new_earn * storage.settings.vested_reward_share / 100;
+        gold.vest(game.creator, vested, storage.settings.vesting_period);
+
+        game.stage = stage;
+        game.score = score;
+        game.status = GameStatus::Ended;
+        close_session(&mut storage.open_games, game.creator, game_id);
 
-            // Emit GameUpdated event
-            self.notify_on(GameEvent::GameUpdated { game_id, score, earn: new_earn })
+        // Update player's highest score and games played
+        player.games_played += 1;
+        if score > player.highest_score {
+            player.highest_score = score;
+        }
+
+        // Keep the all-time and the running season's boards sorted
+        let now = exec::block_timestamp();
+        storage.leaderboard.submit(game.creator, score, now);
+        if let Some(season) = storage.seasons.values().find(|season| season.is_active(now)) {
+            let leaderboard_size = storage.settings.leaderboard_size;
+            storage
+                .season_leaderboards
+                .entry(season.id)
+                .or_insert_with(|| Leaderboard::new(leaderboard_size))
+                .submit(game.creator, score, now);
+        }
+
+        storage.settlements.insert(
+            game_id,
+            GameSettled {
+                game_id,
+                creator: game.creator,
+                score,
+                earn: new_earn,
+                token_ids,
+                amounts,
+                settled_at: exec::block_timestamp(),
+            },
+        );
+
+        // Emit GameUpdated event
+        self.notify_on(GameEvent::GameUpdated { game_id, score, earn: new_earn })
             .expect("Notification Error");
+
+        Ok(())
+    }
+
+    pub fn get_settlement(&self, game_id: u32) -> Option<GameSettled> {
+        let storage = self.get();
+        storage.settlements.get(&game_id).cloned()
+    }
+
+    pub fn create_season(
+        &mut self,
+        start: u64,
+        end: u64,
+        rewards: Vec<RewardTier>,
+    ) -> Result<u32, GameError> {
+        self.check_role(Role::GameOperator)?;
+        let storage = self.get_mut();
+        if start >= end {
+            return Err(GameError::InvalidSeasonPeriod);
+        }
+        if !season::valid_rewards(&rewards, storage.settings.leaderboard_size) {
+            return Err(GameError::InvalidRewardTier);
         }
+        // At most one season may be running at any time
+        if storage
+            .seasons
+            .values()
+            .any(|season| !season.closed && season.overlaps(start, end))
+        {
+            return Err(GameError::SeasonOverlap);
+        }
+
+        storage.last_season_id += 1;
+        let season_id = storage.last_season_id;
+        storage.seasons.insert(
+            season_id,
+            Season {
+                id: season_id,
+                start,
+                end,
+                rewards,
+                closed: false,
+            },
+        );
+
+        self.notify_on(GameEvent::SeasonCreated {
+            season_id,
+            start,
+            end,
+        })
+        .expect("Notification Error");
+        admin::record_admin_action(
+            AdminActionKind::SeasonCreated,
+            &(season_id, start, end, &storage.seasons[&season_id].rewards),
+        );
+        Ok(season_id)
+    }
+
+    /// Closes a season for good, freezing its standings.
+    pub fn close_season(&mut self, season_id: u32) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        let storage = self.get_mut();
+        let season = storage
+            .seasons
+            .get_mut(&season_id)
+            .ok_or(GameError::SeasonNotFound)?;
+        if season.closed {
+            return Err(GameError::SeasonClosed);
+        }
+        season.closed = true;
+
+        self.notify_on(GameEvent::SeasonClosed { season_id })
+            .expect("Notification Error");
+        admin::record_admin_action(AdminActionKind::SeasonClosed, &season_id);
+        Ok(())
+    }
+
+    pub fn set_season_rewards(
+        &mut self,
+        season_id: u32,
+        rewards: Vec<RewardTier>,
+    ) -> Result<(), GameError> {
+        self.check_role(Role::GameOperator)?;
+        let storage = self.get_mut();
+        let season = storage
+            .seasons
+            .get_mut(&season_id)
+            .ok_or(GameError::SeasonNotFound)?;
+        if storage.season_payout_cursor.contains_key(&season_id) {
+            return Err(GameError::RewardsDistributionStarted);
+        }
+        if !season::valid_rewards(&rewards, storage.settings.leaderboard_size) {
+            return Err(GameError::InvalidRewardTier);
+        }
+        admin::record_admin_action(AdminActionKind::SeasonRewardsSet, &(season_id, &rewards));
+        season.rewards = rewards;
+
+        self.notify_on(GameEvent::SeasonRewardsSet { season_id })
+            .expect("Notification Error");
+        Ok(())
+    }
+
+    /// Pays the rewards of a closed season, at most `limit` players per call.
+    /// Call repeatedly until it reports `RewardsAlreadyDistributed`; returns
+    /// how many players this call went through.
+    pub fn distribute_season_rewards(
+        &mut self,
+        season_id: u32,
+        limit: u32,
+    ) -> Result<u32, GameError> {
+        self.check_role(Role::GameOperator)?;
+        // An empty batch would mark the payout started without paying anyone
+        if limit == 0 {
+            return Err(GameError::InvalidBatchSize);
+        }
+        let storage = self.get_mut();
+        let season = storage
+            .seasons
+            .get(&season_id)
+            .ok_or(GameError::SeasonNotFound)?;
+        if !season.closed {
+            return Err(GameError::SeasonNotClosed);
+        }
+        let standings = storage.season_leaderboards.get(&season_id);
+        let rewarded = season
+            .rewards
+            .iter()
+            .map(|tier| tier.to_rank)
+            .max()
+            .unwrap_or(0)
+            .min(standings.map_or(0, Leaderboard::len));
+
+        let cursor = storage
+            .season_payout_cursor
+            .get(&season_id)
+            .copied()
+            .unwrap_or(0);
+        if cursor >= rewarded {
+            return Err(GameError::RewardsAlreadyDistributed);
+        }
+
+        let end = (cursor + limit.min(MAX_REWARD_BATCH)).min(rewarded);
+        let mut gold = GoldService::new();
+        let mut items = ItemService::new();
+        for (index, (player, _)) in standings
+            .into_iter()
+            .flat_map(|standings| standings.page(cursor, end - cursor))
+            .enumerate()
+        {
+            let rank = cursor + index as u32 + 1;
+            // Ranks between tiers get nothing
+            let Some(tier) = season::tier_for_rank(&season.rewards, rank) else {
+                continue;
+            };
+            if !storage.season_paid.insert((season_id, player)) {
+                continue;
+            }
+            services::utils::panicking(|| {
+                gold.mint_internal(player, tier.gold, GoldSource::SeasonReward)
+            });
+            for (token_id, amount) in tier.token_ids.iter().zip(tier.amounts.iter()) {
+                services::utils::panicking(|| items.mint_internal(player, *token_id, *amount));
+            }
+            self.notify_on(GameEvent::SeasonRewardPaid {
+                season_id,
+                rank,
+                player,
+            })
+            .expect("Notification Error");
+        }
+        storage.season_payout_cursor.insert(season_id, end);
+        admin::record_admin_action(
+            AdminActionKind::SeasonRewardsDistributed,
+            &(season_id, cursor, end),
+        );
+        Ok(end - cursor)
+    }
+
+    pub fn is_season_reward_paid(&self, season_id: u32, player: ActorId) -> bool {
+        let storage = self.get();
+        storage.season_paid.contains(&(season_id, player))
+    }
+
+    pub fn get_season(&self, season_id: u32) -> Option<Season> {
+        let storage = self.get();
+        storage.seasons.get(&season_id).cloned()
+    }
+
+    pub fn get_current_season(&self) -> Option<Season> {
+        let storage = self.get();
+        let now = exec::block_timestamp();
+        storage
+            .seasons
+            .values()
+            .find(|season| season.is_active(now))
+            .cloned()
+    }
+
+    pub fn get_season_leaderboard(
+        &self,
+        season_id: u32,
+        offset: u32,
+        limit: u32,
+    ) -> Vec<(ActorId, i32)> {
+        let storage = self.get();
+        storage
+            .season_leaderboards
+            .get(&season_id)
+            .map(|leaderboard| leaderboard.page(offset, limit.min(MAX_PAGE_SIZE)))
+            .unwrap_or_default()
     }
 
     pub fn register_player(&mut self, name: String, avatar_id: u32, avatar_icon: String) {
+        // Nothing to return the error in, the panic refuses the call
+        if Admins::in_migration() {
+            services::utils::panic(GameError::Paused);
+        }
+        self.apply_due_setting_changes();
         let storage = self.get_mut();
         let player = Player {
             name,
@@ -293,7 +1268,9 @@ impl GameService {
             max_stamina: storage.settings.initial_max_stamina,
             min_stamina_block: 0,
         };
-        storage.players.insert(msg::source(), player);
+        if storage.players.insert(msg::source(), player).is_none() {
+            storage.player_ids.push(msg::source());
+        }
     }
 
     pub fn update_player_info(
@@ -301,19 +1278,104 @@ impl GameService {
         name: Option<String>,
         avatar_id: Option<u32>,
         avatar_icon: Option<String>,
-    ) {
+    ) -> Result<(), GameError> {
+        if Admins::in_migration() {
+            return Err(GameError::Paused);
+        }
         let storage = self.get_mut();
-        if let Some(player) = storage.players.get_mut(&msg::source()) {
-            if let Some(name) = name {
-                player.name = name;
-            }
-            if let Some(avatar_id) = avatar_id {
-                player.avatar_id = avatar_id;
-            }
-            if let Some(avatar_icon) = avatar_icon {
-                player.avatar_icon = avatar_icon;
-            }
+        let player = storage
+            .players
+            .get_mut(&msg::source())
+            .ok_or(GameError::PlayerNotFound)?;
+        if let Some(name) = name {
+            player.name = name;
+        }
+        if let Some(avatar_id) = avatar_id {
+            player.avatar_id = avatar_id;
+        }
+        if let Some(avatar_icon) = avatar_icon {
+            player.avatar_icon = avatar_icon;
+        }
+        Ok(())
+    }
+
+    /// Lets `key` call the `allowed_actions` for the caller until `expires_at`.
+    /// Registering the same key again replaces its permissions.
+    pub fn register_session_key(
+        &mut self,
+        key: ActorId,
+        expires_at: u64,
+        allowed_actions: Vec<SessionAction>,
+    ) -> Result<(), GameError> {
+        if Admins::in_migration() {
+            return Err(GameError::Paused);
+        }
+        let owner = msg::source();
+        if key == owner || key.is_zero() || self.active_session_key(&owner).is_some() {
+            return Err(GameError::InvalidSessionKey);
         }
+        let now = exec::block_timestamp();
+        if expires_at <= now || expires_at - now > MAX_SESSION_KEY_LIFETIME {
+            return Err(GameError::InvalidSessionExpiry);
+        }
+        if self
+            .active_session_key(&key)
+            .is_some_and(|session_key| session_key.owner != owner)
+            || self.get().players.contains_key(&key)
+        {
+            return Err(GameError::SessionKeyTaken);
+        }
+
+        self.get_mut().session_keys.insert(
+            key,
+            SessionKey {
+                owner,
+                expires_at,
+                allowed_actions: allowed_actions.clone(),
+            },
+        );
+        self.notify_on(GameEvent::SessionKeyRegistered {
+            owner,
+            key,
+            expires_at,
+            allowed_actions,
+        })
+        .expect("Notification Error");
+        Ok(())
+    }
+
+    /// Called by the owner, or by the key itself to give up its permissions.
+    pub fn revoke_session_key(&mut self, key: ActorId) -> Result<(), GameError> {
+        if Admins::in_migration() {
+            return Err(GameError::Paused);
+        }
+        let caller = msg::source();
+        let storage = self.get_mut();
+        let owner = storage
+            .session_keys
+            .get(&key)
+            .map(|session_key| session_key.owner)
+            .filter(|owner| *owner == caller || key == caller)
+            .ok_or(GameError::SessionKeyNotFound)?;
+        storage.session_keys.remove(&key);
+        self.notify_on(GameEvent::SessionKeyRevoked { owner, key })
+            .expect("Notification Error");
+        Ok(())
+    }
+
+    /// The key's permissions while it hasn't expired.
+    pub fn get_session_key(&self, key: ActorId) -> Option<SessionKey> {
+        self.active_session_key(&key).cloned()
+    }
+
+    pub fn get_session_keys_of(&self, owner: ActorId) -> Vec<(ActorId, SessionKey)> {
+        let now = exec::block_timestamp();
+        self.get()
+            .session_keys
+            .iter()
+            .filter(|(_, session_key)| session_key.owner == owner && session_key.is_active(now))
+            .map(|(key, session_key)| (*key, session_key.clone()))
+            .collect()
     }
 
     pub fn get_player(&self, player_id: ActorId) -> Option<Player> {
@@ -321,68 +1383,299 @@ impl GameService {
         storage.players.get(&player_id).cloned()
     }
 
-    pub fn get_players(&self) -> Vec<(ActorId, Player)> {
+    /// Players in registration order.
+    pub fn get_players(&self, offset: u32, limit: u32) -> Vec<(ActorId, Player)> {
         let storage = self.get();
         storage
-            .players
+            .player_ids
             .iter()
-            .map(|(id, player)| (*id, player.clone()))
+            .skip(offset as usize)
+            .take(limit.min(MAX_PAGE_SIZE) as usize)
+            .filter_map(|id| storage.players.get(id).map(|player| (*id, player.clone())))
             .collect()
     }
 
-    pub fn get_leaderboard(&self) -> Vec<(ActorId, i32)> {
+    /// All-time best scores; only the top `leaderboard_size` players are kept.
+    pub fn get_leaderboard(&self, offset: u32, limit: u32) -> Vec<(ActorId, i32)> {
         let storage = self.get();
-        let mut leaderboard: Vec<(ActorId, i32)> = storage
-            .players
-            .iter()
-            .map(|(id, player)| (*id, player.highest_score))
-            .collect();
-        leaderboard.sort_by(|a, b| b.1.cmp(&a.1));
-        leaderboard
+        storage.leaderboard.page(offset, limit.min(MAX_PAGE_SIZE))
     }
 
-    pub fn get_player_stamina(&self) -> u64 {
+    /// 1-based all-time rank, `None` outside the top `leaderboard_size`.
+    pub fn get_player_rank(&self, player: ActorId) -> Option<u32> {
+        let storage = self.get();
+        storage.leaderboard.rank(&player)
+    }
+
+    pub fn get_player_stamina(&self) -> Result<u64, GameError> {
         let storage = self.get();
         let player = storage
             .players
             .get(&msg::source())
-            .expect("Player not registered");
+            .ok_or(GameError::PlayerNotFound)?;
 
         if player.min_stamina_block == 0 {
-            player.stamina
+            Ok(player.stamina)
         } else {
             let (recovered_stamina, _, _) = self.calculate_stamina(player);
             let new_stamina = (player.stamina + recovered_stamina).min(player.max_stamina);
-            new_stamina
+            Ok(new_stamina)
         }
     }
 
-    pub fn get_player_recovered_block(&self) -> u64 {
+    pub fn get_player_recovered_block(&self) -> Result<u64, GameError> {
         let storage = self.get();
         let player = storage
             .players
             .get(&msg::source())
-            .expect("Player not registered");
+            .ok_or(GameError::PlayerNotFound)?;
 
         if player.min_stamina_block == 0 {
-            0
+            Ok(0)
         } else {
             let (recovered_stamina, remaining_blocks, _) = self.calculate_stamina(player);
             let new_stamina = (player.stamina + recovered_stamina).min(player.max_stamina);
             if new_stamina >= player.max_stamina {
-                0
+                Ok(0)
             } else {
-                storage.settings.stamina_recovery_rate - remaining_blocks
+                Ok(storage.settings.stamina_recovery_rate - remaining_blocks)
             }
         }
     }
 }
 
+fn close_session(open_games: &mut HashMap<ActorId, Vec<u32>>, creator: ActorId, game_id: u32) {
+    if let Some(game_ids) = open_games.get_mut(&creator) {
+        game_ids.retain(|id| *id != game_id);
+        if game_ids.is_empty() {
+            open_games.remove(&creator);
+        }
+    }
+}
+
+/// Signing context for `prefix` bound to this program instance.
+fn signing_context(prefix: &[u8]) -> Vec<u8> {
+    let mut context = prefix.to_vec();
+    context.extend_from_slice(exec::program_id().as_ref());
+    context
+}
+
+/// Checks `sign` over `message` against the active verifier key `key_id`.
+fn verify_signature(
+    verifier_keys: &HashMap<u32, VerifierKey>,
+    key_id: u32,
+    context_prefix: &[u8],
+    message: &[u8],
+    sign: &[u8],
+) -> Result<(), GameError> {
+    let verifier_key = verifier_keys
+        .get(&key_id)
+        .ok_or(GameError::VerifierKeyNotFound)?;
+    if !verifier_key.is_active(exec::block_timestamp()) {
+        return Err(GameError::VerifierKeyInactive);
+    }
+    let public_key = PublicKey::from_bytes(&verifier_key.public_key)
+        .map_err(|_| GameError::InvalidPublicKey)?;
+    let signature = Signature::from_bytes(sign).map_err(|_| GameError::InvalidSignature)?;
+    public_key
+        .verify_simple(&signing_context(context_prefix), message, &signature)
+        .map_err(|_| GameError::InvalidSignature)
+}
+
 impl GameService {
-    fn ensure_is_admin(&self) {
-        if !Admins::is_admin(&msg::source()) {
-            panic!("Not admin")
-        };
+    fn check_role(&self, role: Role) -> Result<(), GameError> {
+        if !Admins::has_role(&msg::source(), role) {
+            return Err(GameError::MissingRole);
+        }
+        Ok(())
+    }
+
+    fn active_session_key(&self, key: &ActorId) -> Option<&'static SessionKey> {
+        let now = exec::block_timestamp();
+        self.get()
+            .session_keys
+            .get(key)
+            .filter(|session_key| session_key.is_active(now))
+    }
+
+    /// The player the caller acts for: the owner when called through a live
+    /// session key, the caller itself otherwise.
+    pub fn acting_player(&self, action: SessionAction) -> Result<ActorId, GameError> {
+        let caller = msg::source();
+        match self.active_session_key(&caller) {
+            Some(session_key) if session_key.allows(action) => Ok(session_key.owner),
+            Some(_) => Err(GameError::SessionActionNotAllowed),
+            None => Ok(caller),
+        }
+    }
+
+    // Verifier key changes without role checks, also run by approved proposals
+    pub fn add_verifier_key_internal(
+        &mut self,
+        key_id: u32,
+        public_key: Vec<u8>,
+        activated_at: u64,
+        expires_at: Option<u64>,
+    ) -> Result<(), GameError> {
+        let public_key =
+            self.check_new_verifier_key(key_id, &public_key, activated_at, expires_at)?;
+        let storage = self.get_mut();
+        storage.verifier_keys.insert(
+            key_id,
+            VerifierKey {
+                public_key,
+                activated_at,
+                expires_at,
+                revoked: false,
+            },
+        );
+        self.notify_on(GameEvent::VerifierKeyAdded {
+            key_id,
+            public_key,
+            activated_at,
+            expires_at,
+        })
+        .expect("Notification Error");
+        Ok(())
+    }
+
+    pub fn set_verifier_key_expiry_internal(
+        &mut self,
+        key_id: u32,
+        expires_at: Option<u64>,
+    ) -> Result<(), GameError> {
+        self.check_verifier_key_expiry(key_id, expires_at)?;
+        let storage = self.get_mut();
+        let key = storage
+            .verifier_keys
+            .get_mut(&key_id)
+            .ok_or(GameError::VerifierKeyNotFound)?;
+        key.expires_at = expires_at;
+        self.notify_on(GameEvent::VerifierKeyExpiryUpdated { key_id, expires_at })
+            .expect("Notification Error");
+        Ok(())
+    }
+
+    /// Validates a new key and returns its bytes.
+    pub fn check_new_verifier_key(
+        &self,
+        key_id: u32,
+        public_key: &[u8],
+        activated_at: u64,
+        expires_at: Option<u64>,
+    ) -> Result<[u8; 32], GameError> {
+        if self.get().verifier_keys.contains_key(&key_id) {
+            return Err(GameError::VerifierKeyExists);
+        }
+        if expires_at.is_some_and(|expires_at| expires_at <= activated_at) {
+            return Err(GameError::InvalidKeyPeriod);
+        }
+        Ok(PublicKey::from_bytes(public_key)
+            .map_err(|_| GameError::InvalidPublicKey)?
+            .to_bytes())
+    }
+
+    pub fn check_verifier_key_expiry(
+        &self,
+        key_id: u32,
+        expires_at: Option<u64>,
+    ) -> Result<(), GameError> {
+        let key = self
+            .get()
+            .verifier_keys
+            .get(&key_id)
+            .ok_or(GameError::VerifierKeyNotFound)?;
+        if expires_at.is_some_and(|expires_at| expires_at <= key.activated_at) {
+            return Err(GameError::InvalidKeyPeriod);
+        }
+        Ok(())
+    }
+
+    pub fn check_verifier_key_exists(&self, key_id: u32) -> Result<(), GameError> {
+        if !self.get().verifier_keys.contains_key(&key_id) {
+            return Err(GameError::VerifierKeyNotFound);
+        }
+        Ok(())
+    }
+
+    pub fn revoke_verifier_key_internal(&mut self, key_id: u32) -> Result<(), GameError> {
+        let storage = self.get_mut();
+        let key = storage
+            .verifier_keys
+            .get_mut(&key_id)
+            .ok_or(GameError::VerifierKeyNotFound)?;
+        key.revoked = true;
+        self.notify_on(GameEvent::VerifierKeyRevoked { key_id })
+            .expect("Notification Error");
+        Ok(())
+    }
+
+    // Queues `change`, at the earliest allowed time unless `activates_at` is
+    // given, and applies it right away when that time is now
+    fn queue_setting_change_internal(
+        &mut self,
+        change: SettingChange,
+        activates_at: Option<u64>,
+    ) -> u32 {
+        let storage = self.get_mut();
+        let now = exec::block_timestamp();
+        let activates_at =
+            activates_at.unwrap_or(now + storage.settings.setting_change_delay);
+        storage.last_setting_change_id += 1;
+        let change_id = storage.last_setting_change_id;
+        let position = storage
+            .pending_setting_changes
+            .partition_point(|pending| pending.activates_at <= activates_at);
+        storage.pending_setting_changes.insert(
+            position,
+            PendingSettingChange {
+                id: change_id,
+                change: change.clone(),
+                queued_at: now,
+                activates_at,
+            },
+        );
+        admin::record_admin_action(
+            AdminActionKind::SettingChangeQueued,
+            &(change_id, &change, activates_at),
+        );
+        self.notify_on(GameEvent::SettingChangeQueued {
+            change_id,
+            change,
+            activates_at,
+        })
+        .expect("Notification Error");
+        self.apply_due_setting_changes();
+        change_id
+    }
+
+    fn apply_due_setting_changes(&mut self) -> u32 {
+        let storage = self.get_mut();
+        let now = exec::block_timestamp();
+        let due = storage
+            .pending_setting_changes
+            .partition_point(|pending| pending.activates_at <= now);
+        let applied: Vec<PendingSettingChange> =
+            storage.pending_setting_changes.drain(..due).collect();
+        for PendingSettingChange { id, change, .. } in applied {
+            change.apply(&mut storage.settings);
+            if let SettingChange::LeaderboardSize(leaderboard_size) = change {
+                storage.leaderboard.set_capacity(leaderboard_size);
+                // Closed seasons keep their final standings
+                let running = storage.seasons.values().find(|season| season.is_active(now));
+                if let Some(leaderboard) =
+                    running.and_then(|season| storage.season_leaderboards.get_mut(&season.id))
+                {
+                    leaderboard.set_capacity(leaderboard_size);
+                }
+            }
+            self.notify_on(GameEvent::SettingChangeApplied {
+                change_id: id,
+                change,
+            })
+            .expect("Notification Error");
+        }
+        due as u32
     }
 
     fn calculate_stamina(&self, player: &Player) -> (u64, u64, u64) {
@@ -0,0 +1,48 @@
+use sails_rs::prelude::*;
+use vmt_service::utils::TokenId;
+
+/// Prize for every rank in `from_rank..=to_rank` (1-based).
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct RewardTier {
+    pub from_rank: u32,
+    pub to_rank: u32,
+    pub gold: U256,
+    pub token_ids: Vec<TokenId>,
+    pub amounts: Vec<U256>,
+}
+
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct Season {
+    pub id: u32,
+    pub start: u64,
+    pub end: u64,
+    pub rewards: Vec<RewardTier>,
+    pub closed: bool,
+}
+
+impl Season {
+    pub fn is_active(&self, now: u64) -> bool {
+        !self.closed && self.start <= now && now < self.end
+    }
+
+    pub fn overlaps(&self, start: u64, end: u64) -> bool {
+        self.start < end && start < self.end
+    }
+}
+
+/// Tiers must cover non-empty 1-based rank ranges within the leaderboard's
+/// `capacity`, as lower ranks are never paid, and pair every item with an amount.
+pub fn valid_rewards(rewards: &[RewardTier], capacity: u32) -> bool {
+    rewards.iter().all(|tier| {
+        tier.from_rank > 0
+            && tier.from_rank <= tier.to_rank
+            && tier.to_rank <= capacity
+            && tier.token_ids.len() == tier.amounts.len()
+    })
+}
+
+pub fn tier_for_rank(rewards: &[RewardTier], rank: u32) -> Option<&RewardTier> {
+    rewards
+        .iter()
+        .find(|tier| tier.from_rank <= rank && rank <= tier.to_rank)
+}
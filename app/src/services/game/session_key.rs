@@ -0,0 +1,32 @@
+use sails_rs::prelude::*;
+
+/// Session keys live at most a week, so a leaked key can't be used for long.
+pub const MAX_SESSION_KEY_LIFETIME: u64 = 7 * 24 * 60 * 60 * 1000;
+
+/// Entry points a session key can call on behalf of its owner.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionAction {
+    /// `create_game` and `start_game`.
+    CreateGame,
+    UpdateGame,
+    /// `MarketService::buy`, paid from the owner's GOLD.
+    Buy,
+}
+
+/// A throwaway account allowed to play for `owner` until `expires_at`.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct SessionKey {
+    pub owner: ActorId,
+    pub expires_at: u64,
+    pub allowed_actions: Vec<SessionAction>,
+}
+
+impl SessionKey {
+    pub fn is_active(&self, now: u64) -> bool {
+        now < self.expires_at
+    }
+
+    pub fn allows(&self, action: SessionAction) -> bool {
+        self.allowed_actions.contains(&action)
+    }
+}
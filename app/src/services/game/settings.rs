@@ -0,0 +1,57 @@
+use super::GameSettings;
+use sails_rs::prelude::*;
+
+/// A single `GameSettings` field together with its new value.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub enum SettingChange {
+    GameTime(u32),
+    MaxEarn(u32),
+    InitialMaxStamina(u64),
+    /// Time (ms) to recover one stamina point, never 0.
+    StaminaRecoveryRate(u64),
+    GracePeriod(u64),
+    LeaderboardSize(u32),
+    ExpiryGasLimit(u64),
+    RefundStaminaOnExpiry(bool),
+    /// Minimum time (ms) between queuing a change and its activation.
+    ChangeDelay(u64),
+    /// Percentage of each `update_game` reward locked in vesting, at most 100.
+    VestedRewardShare(u8),
+    VestingPeriod(u64),
+}
+
+impl SettingChange {
+    pub fn apply(&self, settings: &mut GameSettings) {
+        match *self {
+            SettingChange::GameTime(value) => settings.game_time = value,
+            SettingChange::MaxEarn(value) => settings.max_earn = value,
+            SettingChange::InitialMaxStamina(value) => settings.initial_max_stamina = value,
+            SettingChange::StaminaRecoveryRate(value) => settings.stamina_recovery_rate = value,
+            SettingChange::GracePeriod(value) => settings.grace_period = value,
+            SettingChange::LeaderboardSize(value) => settings.leaderboard_size = value,
+            SettingChange::ExpiryGasLimit(value) => settings.expiry_gas_limit = value,
+            SettingChange::RefundStaminaOnExpiry(value) => settings.refund_stamina_on_expiry = value,
+            SettingChange::ChangeDelay(value) => settings.setting_change_delay = value,
+            SettingChange::VestedRewardShare(value) => settings.vested_reward_share = value,
+            SettingChange::VestingPeriod(value) => settings.vesting_period = value,
+        }
+    }
+
+    /// Whether the new value is in range for its setting.
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            // Stamina recovery divides by the rate
+            SettingChange::StaminaRecoveryRate(rate) => rate > 0,
+            SettingChange::VestedRewardShare(share) => share <= 100,
+            _ => true,
+        }
+    }
+}
+
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq)]
+pub struct PendingSettingChange {
+    pub id: u32,
+    pub change: SettingChange,
+    pub queued_at: u64,
+    pub activates_at: u64,
+}
@@ -0,0 +1,219 @@
+use super::{
+    leaderboard::Leaderboard, GameInfo, GameService, GameSettings, GameSettled,
+    PendingSettingChange, Player, Season, SessionKey, VerifierKey,
+};
+use crate::services::utils::{page, sorted_keys};
+use sails_rs::prelude::*;
+
+/// One piece of the game storage. Open games are indexed again from the
+/// imported games, scheduled expiry messages are not recreated.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug)]
+pub enum GameRecord {
+    Settings(GameSettings),
+    Counters {
+        last_game_id: u32,
+        last_season_id: u32,
+        last_setting_change_id: u32,
+    },
+    VerifierKey {
+        key_id: u32,
+        key: VerifierKey,
+    },
+    /// Exported in registration order.
+    Player {
+        account: ActorId,
+        player: Player,
+    },
+    Game {
+        game_id: u32,
+        game: GameInfo,
+    },
+    Settlement(GameSettled),
+    Season(Season),
+    /// `season_id` is `None` for the all-time board.
+    LeaderboardEntry {
+        season_id: Option<u32>,
+        player: ActorId,
+        score: i32,
+        reached_at: u64,
+    },
+    SeasonPayoutCursor {
+        season_id: u32,
+        cursor: u32,
+    },
+    SeasonPaid {
+        season_id: u32,
+        player: ActorId,
+    },
+    PendingSettingChange(PendingSettingChange),
+    SessionKey {
+        key: ActorId,
+        session_key: SessionKey,
+    },
+}
+
+fn leaderboard_records(
+    season_id: Option<u32>,
+    leaderboard: &Leaderboard,
+) -> impl Iterator<Item = GameRecord> + '_ {
+    leaderboard
+        .entries()
+        .map(move |(player, score, reached_at)| GameRecord::LeaderboardEntry {
+            season_id,
+            player,
+            score,
+            reached_at,
+        })
+}
+
+pub fn export(offset: u32, limit: u32) -> Vec<GameRecord> {
+    let storage = GameService::new().get();
+    let mut season_paid: Vec<(u32, ActorId)> = storage.season_paid.iter().copied().collect();
+    season_paid.sort();
+    let header = [
+        GameRecord::Settings(storage.settings.clone()),
+        GameRecord::Counters {
+            last_game_id: storage.last_game_id,
+            last_season_id: storage.last_season_id,
+            last_setting_change_id: storage.last_setting_change_id,
+        },
+    ];
+    let records = header
+        .into_iter()
+        .chain(sorted_keys(&storage.verifier_keys).into_iter().map(|key_id| {
+            GameRecord::VerifierKey {
+                key_id,
+                key: storage.verifier_keys[&key_id].clone(),
+            }
+        }))
+        .chain(storage.player_ids.iter().filter_map(|account| {
+            storage.players.get(account).map(|player| GameRecord::Player {
+                account: *account,
+                player: player.clone(),
+            })
+        }))
+        .chain(sorted_keys(&storage.games).into_iter().map(|game_id| GameRecord::Game {
+            game_id,
+            game: storage.games[&game_id].clone(),
+        }))
+        .chain(
+            sorted_keys(&storage.settlements)
+                .into_iter()
+                .map(|game_id| GameRecord::Settlement(storage.settlements[&game_id].clone())),
+        )
+        .chain(
+            sorted_keys(&storage.seasons)
+                .into_iter()
+                .map(|season_id| GameRecord::Season(storage.seasons[&season_id].clone())),
+        )
+        .chain(leaderboard_records(None, &storage.leaderboard))
+        .chain(
+            sorted_keys(&storage.season_leaderboards)
+                .into_iter()
+                .flat_map(move |season_id| {
+                    leaderboard_records(Some(season_id), &storage.season_leaderboards[&season_id])
+                }),
+        )
+        .chain(sorted_keys(&storage.season_payout_cursor).into_iter().map(|season_id| {
+            GameRecord::SeasonPayoutCursor {
+                season_id,
+                cursor: storage.season_payout_cursor[&season_id],
+            }
+        }))
+        .chain(
+            season_paid
+                .into_iter()
+                .map(|(season_id, player)| GameRecord::SeasonPaid { season_id, player }),
+        )
+        .chain(
+            storage
+                .pending_setting_changes
+                .iter()
+                .cloned()
+                .map(GameRecord::PendingSettingChange),
+        )
+        .chain(sorted_keys(&storage.session_keys).into_iter().map(|key| {
+            GameRecord::SessionKey {
+                key,
+                session_key: storage.session_keys[&key].clone(),
+            }
+        }));
+    page(records, offset, limit)
+}
+
+pub fn import(records: Vec<GameRecord>) {
+    let storage = GameService::new().get_mut();
+    for record in records {
+        match record {
+            GameRecord::Settings(settings) => {
+                storage.leaderboard.set_capacity(settings.leaderboard_size);
+                storage.settings = settings;
+            }
+            GameRecord::Counters {
+                last_game_id,
+                last_season_id,
+                last_setting_change_id,
+            } => {
+                storage.last_game_id = last_game_id;
+                storage.last_season_id = last_season_id;
+                storage.last_setting_change_id = last_setting_change_id;
+            }
+            GameRecord::VerifierKey { key_id, key } => {
+                storage.verifier_keys.insert(key_id, key);
+            }
+            GameRecord::Player { account, player } => {
+                if storage.players.insert(account, player).is_none() {
+                    storage.player_ids.push(account);
+                }
+            }
+            GameRecord::Game { game_id, game } => {
+                if game.is_open() {
+                    let open_games = storage.open_games.entry(game.creator).or_default();
+                    if !open_games.contains(&game_id) {
+                        open_games.push(game_id);
+                    }
+                }
+                storage.games.insert(game_id, game);
+            }
+            GameRecord::Settlement(settlement) => {
+                storage.settlements.insert(settlement.game_id, settlement);
+            }
+            GameRecord::Season(season) => {
+                storage.seasons.insert(season.id, season);
+            }
+            GameRecord::LeaderboardEntry {
+                season_id,
+                player,
+                score,
+                reached_at,
+            } => {
+                let leaderboard = match season_id {
+                    None => &mut storage.leaderboard,
+                    Some(season_id) => {
+                        let leaderboard_size = storage.settings.leaderboard_size;
+                        storage
+                            .season_leaderboards
+                            .entry(season_id)
+                            .or_insert_with(|| Leaderboard::new(leaderboard_size))
+                    }
+                };
+                leaderboard.submit(player, score, reached_at);
+            }
+            GameRecord::SeasonPayoutCursor { season_id, cursor } => {
+                storage.season_payout_cursor.insert(season_id, cursor);
+            }
+            GameRecord::SeasonPaid { season_id, player } => {
+                storage.season_paid.insert((season_id, player));
+            }
+            GameRecord::PendingSettingChange(change) => {
+                let pending = &mut storage.pending_setting_changes;
+                pending.retain(|queued| queued.id != change.id);
+                let pos = pending.partition_point(|queued| queued.activates_at <= change.activates_at);
+                pending.insert(pos, change);
+            }
+            GameRecord::SessionKey { key, session_key } => {
+                storage.session_keys.insert(key, session_key);
+            }
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use sails_rs::{collections::HashMap, prelude::*};
+
+/// Why GOLD was minted or burned.
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GoldSource {
+    /// `mint` by a minter or an approved mint proposal.
+    AdminMint,
+    AdminBurn,
+    /// Settled and expired games.
+    GameReward,
+    SeasonReward,
+    MarketPurchase,
+}
+
+#[derive(Encode, Decode, TypeInfo, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SourceTotals {
+    pub minted: U256,
+    pub burned: U256,
+}
+
+#[derive(Debug, Default)]
+pub struct Accounting {
+    pub totals: HashMap<GoldSource, SourceTotals>,
+}
+
+static mut ACCOUNTING: Option<Accounting> = None;
+
+impl Accounting {
+    pub fn seed() {
+        unsafe {
+            ACCOUNTING = Some(Accounting::default());
+        }
+    }
+
+    pub fn get() -> &'static Self {
+        unsafe { ACCOUNTING.as_ref().expect("Accounting is not initialized") }
+    }
+
+    pub fn get_mut() -> &'static mut Self {
+        unsafe { ACCOUNTING.as_mut().expect("Accounting is not initialized") }
+    }
+
+    pub fn minted(&mut self, source: GoldSource, value: U256) {
+        let totals = self.totals.entry(source).or_default();
+        totals.minted = totals.minted.saturating_add(value);
+    }
+
+    pub fn burned(&mut self, source: GoldSource, value: U256) {
+        let totals = self.totals.entry(source).or_default();
+        totals.burned = totals.burned.saturating_add(value);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::services::config::EmissionConfig;
+use sails_rs::prelude::*;
+
+/// Game rewards are budgeted per day.
+pub const EPOCH_LENGTH: u64 = 24 * 60 * 60 * 1000;
+
+/// Game reward emission of the running epoch.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct EmissionBudget {
+    pub epoch: u64,
+    pub epoch_budget: U256,
+    pub emitted: U256,
+    /// What game rewards can still mint this epoch, also bounded by the max supply.
+    pub remaining: U256,
+    pub epoch_ends_at: u64,
+}
+
+#[derive(Debug)]
+pub struct Emission {
+    // No mint may push the total supply above this
+    pub max_supply: U256,
+    pub epoch_budget: U256,
+    pub epoch: u64,
+    // Game rewards minted during `epoch`
+    pub emitted: U256,
+}
+
+static mut EMISSION: Option<Emission> = None;
+
+impl Emission {
+    pub fn seed(config: EmissionConfig) {
+        unsafe {
+            EMISSION = Some(Emission {
+                max_supply: config.max_supply,
+                epoch_budget: config.epoch_budget,
+                epoch: 0,
+                emitted: U256::zero(),
+            });
+        }
+    }
+
+    pub fn get() -> &'static Self {
+        unsafe { EMISSION.as_ref().expect("Emission is not initialized") }
+    }
+
+    pub fn get_mut() -> &'static mut Self {
+        unsafe { EMISSION.as_mut().expect("Emission is not initialized") }
+    }
+
+    /// Game rewards minted in the epoch of `now`.
+    fn emitted_at(&self, now: u64) -> U256 {
+        if now / EPOCH_LENGTH == self.epoch {
+            self.emitted
+        } else {
+            U256::zero()
+        }
+    }
+
+    pub fn remaining(&self, now: u64, total_supply: U256) -> U256 {
+        self.epoch_budget
+            .saturating_sub(self.emitted_at(now))
+            .min(self.max_supply.saturating_sub(total_supply))
+    }
+
+    pub fn budget(&self, now: u64, total_supply: U256) -> EmissionBudget {
+        let epoch = now / EPOCH_LENGTH;
+        EmissionBudget {
+            epoch,
+            epoch_budget: self.epoch_budget,
+            emitted: self.emitted_at(now),
+            remaining: self.remaining(now, total_supply),
+            epoch_ends_at: (epoch + 1).saturating_mul(EPOCH_LENGTH),
+        }
+    }
+
+    /// Takes up to `value` from the budget of the epoch of `now` and returns
+    /// the part that may be minted.
+    pub fn take(&mut self, now: u64, total_supply: U256, value: U256) -> U256 {
+        let granted = value.min(self.remaining(now, total_supply));
+        self.emitted = self.emitted_at(now) + granted;
+        self.epoch = now / EPOCH_LENGTH;
+        granted
+    }
+}
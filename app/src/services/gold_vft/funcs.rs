@@ -1,26 +1,28 @@
+use super::GoldError;
 use sails_rs::prelude::*;
-use vft_service::{
-    funcs,
-    utils::{Error, Result, *},
-};
+use vft_service::{funcs, utils::*};
 
 pub fn mint(
     balances: &mut BalancesMap,
     total_supply: &mut U256,
+    max_supply: U256,
     to: ActorId,
     value: U256,
-) -> Result<bool> {
+) -> Result<bool, GoldError> {
     if value.is_zero() {
         return Ok(false);
     }
 
     let new_total_supply = total_supply
         .checked_add(value)
-        .ok_or(Error::NumericOverflow)?;
+        .ok_or(GoldError::NumericOverflow)?;
+    if new_total_supply > max_supply {
+        return Err(GoldError::MaxSupplyExceeded);
+    }
 
     let new_to = funcs::balance_of(balances, to)
         .checked_add(value)
-        .ok_or(Error::NumericOverflow)?;
+        .ok_or(GoldError::NumericOverflow)?;
 
     balances.insert(to, new_to);
     *total_supply = new_total_supply;
@@ -33,15 +35,17 @@ pub fn burn(
     total_supply: &mut U256,
     from: ActorId,
     value: U256,
-) -> Result<bool> {
+) -> Result<bool, GoldError> {
     if value.is_zero() {
         return Ok(false);
     }
-    let new_total_supply = total_supply.checked_sub(value).ok_or(Error::Underflow)?;
+    let new_total_supply = total_supply
+        .checked_sub(value)
+        .ok_or(GoldError::InsufficientBalance)?;
 
     let new_from = funcs::balance_of(balances, from)
         .checked_sub(value)
-        .ok_or(Error::InsufficientBalance)?;
+        .ok_or(GoldError::InsufficientBalance)?;
 
     if !new_from.is_zero() {
         balances.insert(from, new_from);
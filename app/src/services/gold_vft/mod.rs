@@ -1,14 +1,43 @@
-use gstd::msg;
+use gstd::{exec, msg};
 use sails_rs::{gstd::service, prelude::*};
+pub mod accounting;
+pub mod emission;
 mod funcs;
-use crate::services;
+pub mod snapshot;
+pub mod vesting;
+use crate::services::{
+    self,
+    config::{EmissionConfig, TokenConfig},
+};
+use accounting::{Accounting, GoldSource, SourceTotals};
+use emission::{Emission, EmissionBudget};
+use vesting::{Vesting, VestingInfo};
 use vft_service::{Service as VftService, Storage};
-use crate::admin::Admins;
+use crate::admin::{self, audit::AdminActionKind, Admins, PauseTarget, Role};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum GoldError {
+    MissingRole,
+    /// Mints above the large mint limit need an approved proposal.
+    ProposalRequired,
+    Paused,
+    /// The sender is banned while bans block transfers.
+    Banned,
+    NumericOverflow,
+    InsufficientBalance,
+    MaxSupplyExceeded,
+    /// The amount exceeds the balance that is not locked in vesting.
+    BalanceLocked,
+    NothingToClaim,
+}
 
 #[derive(Encode, Decode, TypeInfo)]
 pub enum Event {
-    Minted { to: ActorId, value: U256 },
-    Burned { from: ActorId, value: U256 },
+    Minted { to: ActorId, value: U256, source: GoldSource },
+    Burned { from: ActorId, value: U256, source: GoldSource },
+    EpochBudgetSet { epoch_budget: U256 },
+    VestingStarted { account: ActorId, value: U256, unlocks_at: u64 },
+    VestingClaimed { account: ActorId, value: U256 },
 }
 
 #[derive(Clone)]
@@ -17,9 +46,12 @@ pub struct GoldService {
 }
 
 impl GoldService {
-    pub fn seed() -> Self {
+    pub fn seed(config: TokenConfig, emission: EmissionConfig) -> Self {
+        Emission::seed(emission);
+        Accounting::seed();
+        Vesting::seed();
         GoldService {
-            vft: VftService::seed("Game Gold".to_owned(), "GOLD".to_owned(), 2),
+            vft: VftService::seed(config.name, config.symbol, config.decimals),
         }
     }
 }
@@ -32,66 +64,227 @@ impl GoldService {
         }
     }
 
-    pub fn mint(&mut self, to: ActorId, value: U256) -> bool {
-        self.ensure_is_admin();
-        self.mint_internal(to, value)
+    pub fn mint(&mut self, to: ActorId, value: U256) -> Result<bool, GoldError> {
+        self.check_role(Role::Minter)?;
+        if value > Admins::large_mint_limit() && Admins::requires_proposal() {
+            return Err(GoldError::ProposalRequired);
+        }
+        let mutated = self.mint_internal(to, value, GoldSource::AdminMint)?;
+        admin::record_admin_action(AdminActionKind::GoldMinted, &(to, value));
+        Ok(mutated)
+    }
+
+    pub fn burn(&mut self, from: ActorId, value: U256) -> Result<bool, GoldError> {
+        self.check_role(Role::Minter)?;
+        let mutated = self.burn_internal(from, value, GoldSource::AdminBurn)?;
+        admin::record_admin_action(AdminActionKind::GoldBurned, &(from, value));
+        Ok(mutated)
+    }
+
+    /// Budget of game rewards per epoch, taking effect in the running epoch.
+    pub fn set_epoch_budget(&mut self, epoch_budget: U256) -> Result<(), GoldError> {
+        self.check_role(Role::Minter)?;
+        if Admins::requires_proposal() {
+            return Err(GoldError::ProposalRequired);
+        }
+        self.set_epoch_budget_internal(epoch_budget);
+        admin::record_admin_action(AdminActionKind::EpochBudgetSet, &epoch_budget);
+        Ok(())
+    }
+
+    pub fn max_supply(&self) -> U256 {
+        Emission::get().max_supply
+    }
+
+    pub fn emission_budget(&self) -> EmissionBudget {
+        Emission::get().budget(exec::block_timestamp(), *Storage::total_supply())
+    }
+
+    /// GOLD minted and burned so far, per source.
+    pub fn supply_by_source(&self) -> Vec<(GoldSource, SourceTotals)> {
+        let mut totals: Vec<(GoldSource, SourceTotals)> = Accounting::get()
+            .totals
+            .iter()
+            .map(|(source, totals)| (*source, *totals))
+            .collect();
+        totals.sort();
+        totals
+    }
+
+    /// Unlocks the caller's GOLD that has vested so far. Returns the amount.
+    pub fn claim_vested(&mut self) -> Result<U256, GoldError> {
+        if Admins::in_migration() {
+            return Err(GoldError::Paused);
+        }
+        let account = msg::source();
+        let value = Vesting::get_mut().claim(&account, exec::block_timestamp());
+        if value.is_zero() {
+            return Err(GoldError::NothingToClaim);
+        }
+        self.notify(Event::VestingClaimed { account, value });
+        Ok(value)
+    }
+
+    pub fn vesting_of(&self, account: ActorId) -> VestingInfo {
+        Vesting::get().info(&account, exec::block_timestamp())
+    }
+
+    // Overrides of the base transfers that honour the pause switch, bans and
+    // vesting locks
+    pub fn transfer(&mut self, to: ActorId, value: U256) -> Result<bool, GoldError> {
+        let from = msg::source();
+        Self::check_transfer_allowed(&from)?;
+        Self::check_unlocked(&from, value)?;
+        Ok(self.vft.transfer(to, value))
     }
 
-    pub fn burn(&mut self, from: ActorId, value: U256) -> bool {
-        self.ensure_is_admin();
-        self.burn_internal(from, value)
+    pub fn transfer_from(
+        &mut self,
+        from: ActorId,
+        to: ActorId,
+        value: U256,
+    ) -> Result<bool, GoldError> {
+        Self::check_transfer_allowed(&from)?;
+        Self::check_unlocked(&from, value)?;
+        Ok(self.vft.transfer_from(from, to, value))
+    }
+
+    // Allowances are part of the snapshot, so they stay put during a migration
+    pub fn approve(&mut self, spender: ActorId, value: U256) -> Result<bool, GoldError> {
+        if Admins::in_migration() {
+            return Err(GoldError::Paused);
+        }
+        Ok(self.vft.approve(spender, value))
     }
 }
 
 impl GoldService {
-    fn ensure_is_admin(&self) {
-        if !Admins::is_admin(&msg::source()) {
-            panic!("Not admin")
-        };
+    fn check_role(&self, role: Role) -> Result<(), GoldError> {
+        if !Admins::has_role(&msg::source(), role) {
+            return Err(GoldError::MissingRole);
+        }
+        Ok(())
+    }
+
+    fn check_transfer_allowed(from: &ActorId) -> Result<(), GoldError> {
+        if Admins::is_paused(PauseTarget::GoldTransfers) {
+            return Err(GoldError::Paused);
+        }
+        if Admins::is_transfer_blocked(from) {
+            return Err(GoldError::Banned);
+        }
+        Ok(())
+    }
+
+    fn check_unlocked(from: &ActorId, value: U256) -> Result<(), GoldError> {
+        if value > Self::unlocked_balance_of(*from) {
+            return Err(GoldError::BalanceLocked);
+        }
+        Ok(())
+    }
+
+    // Game and market calls mint and burn too, emit under the Vft route
+    fn notify(&mut self, event: Event) {
+        services::utils::on_route(services::utils::VFT_ROUTE, || self.notify_on(event))
+            .expect("Notification Error");
     }
 
     // Internal mint method without admin check
-    pub fn mint_internal(&mut self, to: ActorId, value: U256) -> bool {
-        let mutated = services::utils::panicking(|| {
-            funcs::mint(Storage::balances(), Storage::total_supply(), to, value)
-        });
+    pub fn mint_internal(
+        &mut self,
+        to: ActorId,
+        value: U256,
+        source: GoldSource,
+    ) -> Result<bool, GoldError> {
+        let mutated = funcs::mint(
+            Storage::balances(),
+            Storage::total_supply(),
+            Emission::get().max_supply,
+            to,
+            value,
+        )?;
         if mutated {
-            self.notify_on(Event::Minted { to, value })
-                .expect("Notification Error");
+            Accounting::get_mut().minted(source, value);
+            self.notify(Event::Minted { to, value, source });
         }
-        mutated
+        Ok(mutated)
     }
 
     // Internal burn method without admin check
-    pub fn burn_internal(&mut self, from: ActorId, value: U256) -> bool {
-        let mutated = services::utils::panicking(|| {
-            funcs::burn(Storage::balances(), Storage::total_supply(), from, value)
-        });
+    pub fn burn_internal(
+        &mut self,
+        from: ActorId,
+        value: U256,
+        source: GoldSource,
+    ) -> Result<bool, GoldError> {
+        let mutated = funcs::burn(Storage::balances(), Storage::total_supply(), from, value)?;
         if mutated {
-            self.notify_on(Event::Burned { from, value })
-                .expect("Notification Error");
+            Accounting::get_mut().burned(source, value);
+            self.notify(Event::Burned { from, value, source });
         }
-        mutated
+        Ok(mutated)
     }
 
-    pub fn burn_internal_notify_off(from: ActorId, value: U256) -> bool {
-        let mutated = services::utils::panicking(|| {
-            funcs::burn(Storage::balances(), Storage::total_supply(), from, value)
-        });
-        mutated
+    /// Whether minting `value` would stay within the max supply.
+    pub fn check_mint(value: U256) -> Result<(), GoldError> {
+        let total_supply = Storage::total_supply()
+            .checked_add(value)
+            .ok_or(GoldError::NumericOverflow)?;
+        if total_supply > Emission::get().max_supply {
+            return Err(GoldError::MaxSupplyExceeded);
+        }
+        Ok(())
     }
 
-    pub fn mint_internal_notify_off( to: ActorId, value: U256) -> bool {
-        let mutated = services::utils::panicking(|| {
-            funcs::mint(Storage::balances(), Storage::total_supply(), to, value)
+    /// Mints a game reward out of the epoch budget. Once the budget runs
+    /// short the reward is scaled down to what is left, the amount actually
+    /// minted is returned.
+    pub fn mint_reward(&mut self, to: ActorId, value: U256) -> U256 {
+        let granted = Emission::get_mut().take(
+            exec::block_timestamp(),
+            *Storage::total_supply(),
+            value,
+        );
+        services::utils::panicking(|| self.mint_internal(to, granted, GoldSource::GameReward));
+        granted
+    }
+
+    /// Locks `value` of what `to` already holds, unlocking it linearly over
+    /// `duration` ms from now. Schedules merged to stay within the limit
+    /// unlock what they had vested on the way.
+    pub fn vest(&mut self, to: ActorId, value: U256, duration: u64) {
+        if value.is_zero() {
+            return;
+        }
+        let start = exec::block_timestamp();
+        let claimed = Vesting::get_mut().grant(to, value, start, duration);
+        if !claimed.is_zero() {
+            self.notify(Event::VestingClaimed {
+                account: to,
+                value: claimed,
+            });
+        }
+        self.notify(Event::VestingStarted {
+            account: to,
+            value,
+            unlocks_at: start.saturating_add(duration),
         });
-        mutated
+    }
+
+    pub fn set_epoch_budget_internal(&mut self, epoch_budget: U256) {
+        Emission::get_mut().epoch_budget = epoch_budget;
+        self.notify(Event::EpochBudgetSet { epoch_budget });
     }
 
     pub fn balance_of_mine( owner: ActorId) -> U256 {
         Storage::balances().get(&owner).cloned().unwrap_or_default()
     }
 
+    /// Balance minus the GOLD still locked in vesting.
+    pub fn unlocked_balance_of(owner: ActorId) -> U256 {
+        Self::balance_of_mine(owner).saturating_sub(Vesting::get().locked(&owner))
+    }
+
 }
 
 impl AsRef<VftService> for GoldService {
@@ -0,0 +1,131 @@
+use super::{
+    accounting::{Accounting, GoldSource, SourceTotals},
+    emission::Emission,
+    vesting::{Vesting, VestingSchedule},
+    GoldError,
+};
+use crate::services::utils::{page, sorted_keys};
+use sails_rs::prelude::*;
+use vft_service::Storage;
+
+/// One piece of the GOLD storage. The total supply follows from the balances,
+/// the max supply stays whatever the new instance was deployed with.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum GoldRecord {
+    Emission {
+        epoch_budget: U256,
+        epoch: u64,
+        emitted: U256,
+    },
+    SourceTotals {
+        source: GoldSource,
+        totals: SourceTotals,
+    },
+    Balance {
+        account: ActorId,
+        value: U256,
+    },
+    Allowance {
+        owner: ActorId,
+        spender: ActorId,
+        value: U256,
+    },
+    /// Every schedule of `account`, replacing the ones it has.
+    Vesting {
+        account: ActorId,
+        schedules: Vec<VestingSchedule>,
+    },
+}
+
+pub fn export(offset: u32, limit: u32) -> Vec<GoldRecord> {
+    let balances = Storage::balances();
+    let allowances = Storage::allowances();
+    let emission = Emission::get();
+    let totals = &Accounting::get().totals;
+    let vesting = &Vesting::get().schedules;
+    let header = [GoldRecord::Emission {
+        epoch_budget: emission.epoch_budget,
+        epoch: emission.epoch,
+        emitted: emission.emitted,
+    }];
+    let records = header
+        .into_iter()
+        .chain(sorted_keys(totals).into_iter().map(|source| GoldRecord::SourceTotals {
+            source,
+            totals: totals[&source],
+        }))
+        .chain(sorted_keys(balances).into_iter().map(|account| GoldRecord::Balance {
+            account,
+            value: balances[&account],
+        }))
+        .chain(
+            sorted_keys(allowances)
+                .into_iter()
+                .map(|(owner, spender)| GoldRecord::Allowance {
+                    owner,
+                    spender,
+                    value: allowances[&(owner, spender)],
+                }),
+        )
+        .chain(sorted_keys(vesting).into_iter().map(|account| GoldRecord::Vesting {
+            account,
+            schedules: vesting[&account].clone(),
+        }));
+    page(records, offset, limit)
+}
+
+/// Balances replace whatever the account holds, adjusting the total supply,
+/// which may not end up above the max supply.
+pub fn import(records: Vec<GoldRecord>) -> Result<(), GoldError> {
+    let balances = Storage::balances();
+    let total_supply = Storage::total_supply();
+    for record in records {
+        match record {
+            GoldRecord::Emission {
+                epoch_budget,
+                epoch,
+                emitted,
+            } => {
+                let emission = Emission::get_mut();
+                emission.epoch_budget = epoch_budget;
+                emission.epoch = epoch;
+                emission.emitted = emitted;
+            }
+            GoldRecord::SourceTotals { source, totals } => {
+                Accounting::get_mut().totals.insert(source, totals);
+            }
+            GoldRecord::Balance { account, value } => {
+                let previous = balances.get(&account).copied().unwrap_or_default();
+                let new_total = total_supply
+                    .checked_sub(previous)
+                    .and_then(|total| total.checked_add(value))
+                    .ok_or(GoldError::NumericOverflow)?;
+                if new_total > Emission::get().max_supply {
+                    return Err(GoldError::MaxSupplyExceeded);
+                }
+                *total_supply = new_total;
+                if value.is_zero() {
+                    balances.remove(&account);
+                } else {
+                    balances.insert(account, value);
+                }
+            }
+            GoldRecord::Allowance {
+                owner,
+                spender,
+                value,
+            } => {
+                Storage::allowances().insert((owner, spender), value);
+            }
+            GoldRecord::Vesting { account, schedules } => {
+                let vesting = &mut Vesting::get_mut().schedules;
+                if schedules.is_empty() {
+                    vesting.remove(&account);
+                } else {
+                    vesting.insert(account, schedules);
+                }
+            }
+        }
+    }
+    Ok(())
+}
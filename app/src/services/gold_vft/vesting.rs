@@ -0,0 +1,143 @@
+use sails_rs::{collections::HashMap, prelude::*};
+
+/// Locked part of a game reward. It unlocks linearly from `start` over
+/// `duration` and stays locked until claimed.
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct VestingSchedule {
+    pub total: U256,
+    pub claimed: U256,
+    pub start: u64,
+    pub duration: u64,
+}
+
+impl VestingSchedule {
+    pub fn ends_at(&self) -> u64 {
+        self.start.saturating_add(self.duration)
+    }
+
+    pub fn vested(&self, now: u64) -> U256 {
+        let elapsed = now.saturating_sub(self.start);
+        if elapsed >= self.duration {
+            return self.total;
+        }
+        self.total.saturating_mul(elapsed.into()) / U256::from(self.duration)
+    }
+
+    pub fn claimable(&self, now: u64) -> U256 {
+        self.vested(now).saturating_sub(self.claimed)
+    }
+
+    pub fn locked(&self) -> U256 {
+        self.total - self.claimed
+    }
+}
+
+/// Most schedules an account keeps, so transfers only look at a few of them.
+pub const MAX_VESTING_SCHEDULES: usize = 8;
+
+#[derive(Encode, Decode, TypeInfo, Clone, Debug, PartialEq, Eq)]
+pub struct VestingInfo {
+    /// Part of the balance that can't be spent yet.
+    pub locked: U256,
+    /// What `claim_vested` would unlock now.
+    pub claimable: U256,
+    /// Ordered by the time they fully unlock.
+    pub schedules: Vec<VestingSchedule>,
+}
+
+#[derive(Debug, Default)]
+pub struct Vesting {
+    pub schedules: HashMap<ActorId, Vec<VestingSchedule>>,
+}
+
+static mut VESTING: Option<Vesting> = None;
+
+impl Vesting {
+    pub fn seed() {
+        unsafe {
+            VESTING = Some(Vesting::default());
+        }
+    }
+
+    pub fn get() -> &'static Self {
+        unsafe { VESTING.as_ref().expect("Vesting is not initialized") }
+    }
+
+    pub fn get_mut() -> &'static mut Self {
+        unsafe { VESTING.as_mut().expect("Vesting is not initialized") }
+    }
+
+    pub fn locked(&self, account: &ActorId) -> U256 {
+        self.schedules
+            .get(account)
+            .into_iter()
+            .flatten()
+            .fold(U256::zero(), |locked, schedule| locked + schedule.locked())
+    }
+
+    pub fn info(&self, account: &ActorId, now: u64) -> VestingInfo {
+        let schedules = self.schedules.get(account).cloned().unwrap_or_default();
+        VestingInfo {
+            locked: self.locked(account),
+            claimable: schedules
+                .iter()
+                .fold(U256::zero(), |claimable, schedule| claimable + schedule.claimable(now)),
+            schedules,
+        }
+    }
+
+    /// Unlocks everything vested so far and drops the schedules that are done.
+    /// Returns the unlocked amount.
+    pub fn claim(&mut self, account: &ActorId, now: u64) -> U256 {
+        let Some(schedules) = self.schedules.get_mut(account) else {
+            return U256::zero();
+        };
+        let mut claimed = U256::zero();
+        for schedule in schedules.iter_mut() {
+            let claimable = schedule.claimable(now);
+            schedule.claimed += claimable;
+            claimed += claimable;
+        }
+        schedules.retain(|schedule| !schedule.locked().is_zero());
+        if schedules.is_empty() {
+            self.schedules.remove(account);
+        }
+        claimed
+    }
+
+    /// Adds a schedule for `value` unlocking over `duration` from `now`. Past
+    /// `MAX_VESTING_SCHEDULES` the two schedules that end first are merged
+    /// into one ending with the first, so no grant unlocks later than it was
+    /// due; what they had vested is unlocked on the way and returned.
+    pub fn grant(&mut self, account: ActorId, value: U256, now: u64, duration: u64) -> U256 {
+        let schedules = self.schedules.entry(account).or_default();
+        let schedule = VestingSchedule {
+            total: value,
+            claimed: U256::zero(),
+            start: now,
+            duration,
+        };
+        let pos = schedules.partition_point(|queued| queued.ends_at() <= schedule.ends_at());
+        schedules.insert(pos, schedule);
+        if schedules.len() <= MAX_VESTING_SCHEDULES {
+            return U256::zero();
+        }
+
+        let second = schedules.remove(1);
+        let first = &schedules[0];
+        let released = first.claimable(now) + second.claimable(now);
+        let locked = first.locked() + second.locked() - released;
+        let ends_at = first.ends_at();
+        if locked.is_zero() {
+            schedules.remove(0);
+        } else {
+            schedules[0] = VestingSchedule {
+                total: locked,
+                claimed: U256::zero(),
+                start: now,
+                duration: ends_at.saturating_sub(now),
+            };
+        }
+        released
+    }
+}
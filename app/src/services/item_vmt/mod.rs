@@ -5,11 +5,13 @@ use sails_rs::{
     prelude::*,
 };
 mod funcs;
+pub mod snapshot;
 use crate::services;
 pub mod utils;
+use utils::Result;
 use utils::*;
 use vmt_service::{Service as VmtService, Storage};
-use crate::admin::Admins;
+use crate::admin::{self, audit::AdminActionKind, Admins, PauseTarget, Role};
 
 #[derive(Default)]
 pub struct ItemStorage {
@@ -39,36 +41,12 @@ pub struct ItemService {
 }
 
 impl ItemService {
-    pub fn seed() -> Self {
+    pub fn seed(items: Vec<(TokenId, TokenMetadata)>) -> Self {
         unsafe {
-
-            let mut token_metadata: HashMap<TokenId, TokenMetadata> = HashMap::new();
-            let candy_metadata = TokenMetadata {
-                title: Some("Candy".to_string()),
-                description: Some("Candy".to_string()),
-                media: None,
-                reference: None,
-                // Replace with actual field name and value
-            };
-            let hummer_metadata = TokenMetadata {
-                title: Some("Hummer".to_string()),
-                description: Some("Hummer".to_string()),
-                media: None,
-                reference: None,
-                // Replace with actual field name and value
-            };
-            token_metadata.insert(110.into(), candy_metadata);
-            token_metadata.insert(220.into(), hummer_metadata);
-            
-
-
             EXTENDED_STORAGE = Some(ItemStorage {
-                token_metadata: token_metadata,
+                token_metadata: items.into_iter().collect(),
                 owners: HashMap::new(),
             });
-
-
-
         };
         ItemService {
             vmt: <VmtService>::seed("GameItem".to_owned(), "Item".to_owned(), 0),
@@ -107,56 +85,105 @@ impl ItemService {
         }
     }
 
-    pub fn create_token_metadata(&mut self, id: TokenId, metadata: TokenMetadata) {
-        self.ensure_is_admin();
+    pub fn create_token_metadata(&mut self, id: TokenId, metadata: TokenMetadata) -> Result<()> {
+        self.check_role(Role::ItemManager)?;
         let storage = self.get_mut();
+        admin::record_admin_action(AdminActionKind::ItemMetadataCreated, &(id, &metadata));
         storage.token_metadata.insert(id, metadata);
+        Ok(())
+    }
+
+    pub fn mint(&mut self, to: ActorId, id: TokenId, amount: U256) -> Result<()> {
+        self.check_role(Role::ItemManager)?;
+        self.mint_internal(to, id, amount)?;
+        admin::record_admin_action(AdminActionKind::ItemsMinted, &(to, vec![id], vec![amount]));
+        Ok(())
+    }
+
+    pub fn mint_batch(&mut self, to: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) -> Result<()> {
+        self.check_role(Role::ItemManager)?;
+        let params = (to, ids.clone(), amounts.clone());
+        self.mint_batch_internal(to, ids, amounts)?;
+        admin::record_admin_action(AdminActionKind::ItemsMinted, &params);
+        Ok(())
     }
 
-    pub fn mint(&mut self, to: ActorId, id: TokenId, amount: U256) {
-        self.ensure_is_admin();
-        self.mint_internal(to, id, amount);
+    pub fn burn(&mut self, from: ActorId, id: TokenId, amount: U256) -> Result<()> {
+        self.check_role(Role::ItemManager)?;
+        self.burn_internal(from, id, amount)?;
+        admin::record_admin_action(AdminActionKind::ItemsBurned, &(from, vec![id], vec![amount]));
+        Ok(())
     }
 
-    pub fn mint_batch(&mut self, to: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) {
-        self.ensure_is_admin();
-        self.mint_batch_internal(to, ids, amounts);
+    pub fn burn_batch(&mut self, from: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) -> Result<()> {
+        self.check_role(Role::ItemManager)?;
+        let params = (from, ids.clone(), amounts.clone());
+        self.burn_batch_internal(from, ids, amounts)?;
+        admin::record_admin_action(AdminActionKind::ItemsBurned, &params);
+        Ok(())
     }
 
-    pub fn burn(&mut self, from: ActorId, id: TokenId, amount: U256) {
-        self.ensure_is_admin();
-        self.burn_internal(from, id, amount);
+    // Overrides of the base transfers that honour the pause switch and bans
+    pub fn transfer_from(&mut self, from: ActorId, to: ActorId, id: TokenId, amount: U256) -> Result<()> {
+        Self::check_transfer_allowed(&from)?;
+        self.vmt.transfer_from(from, to, id, amount);
+        Ok(())
     }
 
-    pub fn burn_batch(&mut self, from: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) {
-        self.ensure_is_admin();
-        self.burn_batch_internal(from, ids, amounts);
+    pub fn batch_transfer_from(
+        &mut self,
+        from: ActorId,
+        to: ActorId,
+        ids: Vec<TokenId>,
+        amounts: Vec<U256>,
+    ) -> Result<()> {
+        Self::check_transfer_allowed(&from)?;
+        self.vmt.batch_transfer_from(from, to, ids, amounts);
+        Ok(())
     }
 }
 
 impl ItemService {
-    fn ensure_is_admin(&self) {
-        if !Admins::is_admin(&msg::source()) {
-            panic!("Not admin")
-        };
+    fn check_role(&self, role: Role) -> Result<()> {
+        if !Admins::has_role(&msg::source(), role) {
+            return Err(Error::MissingRole);
+        }
+        Ok(())
+    }
+
+    fn check_transfer_allowed(from: &ActorId) -> Result<()> {
+        if Admins::is_paused(PauseTarget::ItemTransfers) {
+            return Err(Error::Paused);
+        }
+        if Admins::is_transfer_blocked(from) {
+            return Err(Error::Banned);
+        }
+        Ok(())
     }
 
     // Internal mint method without admin check
-    pub fn mint_internal(&mut self, to: ActorId, id: TokenId, amount: U256) {
+    pub fn mint_internal(&mut self, to: ActorId, id: TokenId, amount: U256) -> Result<()> {
         let storage = self.get_mut();
         let metadata = storage.token_metadata.get(&id).cloned();
-        let event = services::utils::panicking(|| {
-            funcs::mint(
-                Storage::balances(),
-                Storage::total_supply(),
-                storage,
-                to,
-                vec![id],
-                vec![amount],
-                vec![metadata],
-            )
-        });
+        let event = funcs::mint(
+            Storage::balances(),
+            Storage::total_supply(),
+            storage,
+            to,
+            vec![id],
+            vec![amount],
+            vec![metadata],
+        )?;
         self.notify_on(event).expect("Notification Error");
+        Ok(())
+    }
+
+    pub fn balance_of_mine(owner: ActorId, id: TokenId) -> U256 {
+        Storage::balances()
+            .get(&id)
+            .and_then(|balances| balances.get(&owner))
+            .copied()
+            .unwrap_or_default()
     }
 
     pub fn mint_internal_notify_off(storage:&mut ItemStorage, to: ActorId, id: TokenId, amount: U256) {
@@ -177,36 +204,34 @@ impl ItemService {
 
 
     // Internal mint batch method without admin check
-    pub fn mint_batch_internal(&mut self, to: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) {
+    pub fn mint_batch_internal(&mut self, to: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) -> Result<()> {
         let storage = self.get_mut();
         let metadata: Vec<Option<TokenMetadata>> = ids.iter().map(|id| storage.token_metadata.get(id).cloned()).collect();
-        let event = services::utils::panicking(|| {
-            funcs::mint(
-                Storage::balances(),
-                Storage::total_supply(),
-                storage,
-                to,
-                ids,
-                amounts,
-                metadata,
-            )
-        });
+        let event = funcs::mint(
+            Storage::balances(),
+            Storage::total_supply(),
+            storage,
+            to,
+            ids,
+            amounts,
+            metadata,
+        )?;
         self.notify_on(event).expect("Notification Error");
+        Ok(())
     }
 
     // Internal burn method without admin check
-    pub fn burn_internal(&mut self, from: ActorId, id: TokenId, amount: U256) {
-        let event = services::utils::panicking(|| {
-            funcs::burn(
-                Storage::balances(),
-                Storage::total_supply(),
-                self.get_mut(),
-                from,
-                vec![id],
-                vec![amount],
-            )
-        });
+    pub fn burn_internal(&mut self, from: ActorId, id: TokenId, amount: U256) -> Result<()> {
+        let event = funcs::burn(
+            Storage::balances(),
+            Storage::total_supply(),
+            self.get_mut(),
+            from,
+            vec![id],
+            vec![amount],
+        )?;
         self.notify_on(event).expect("Notification Error");
+        Ok(())
     }
 
     pub fn burn_internal_notify_off(storage:&mut ItemStorage, from: ActorId, id: TokenId, amount: U256) {
@@ -223,18 +248,17 @@ impl ItemService {
     }
 
     // Internal burn batch method without admin check
-    pub fn burn_batch_internal(&mut self, from: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) {
-        let event = services::utils::panicking(|| {
-            funcs::burn(
-                Storage::balances(),
-                Storage::total_supply(),
-                self.get_mut(),
-                from,
-                ids,
-                amounts,
-            )
-        });
+    pub fn burn_batch_internal(&mut self, from: ActorId, ids: Vec<TokenId>, amounts: Vec<U256>) -> Result<()> {
+        let event = funcs::burn(
+            Storage::balances(),
+            Storage::total_supply(),
+            self.get_mut(),
+            from,
+            ids,
+            amounts,
+        )?;
         self.notify_on(event).expect("Notification Error");
+        Ok(())
     }
 }
 
@@ -0,0 +1,84 @@
+use super::{
+    utils::{Error, Result, TokenId, TokenMetadata},
+    ItemService,
+};
+use crate::services::utils::{page, sorted_keys};
+use sails_rs::prelude::*;
+use vmt_service::Storage;
+
+/// One piece of the item storage. Total supplies follow from the balances,
+/// operator approvals have to be granted again on the new instance.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum ItemRecord {
+    Metadata {
+        token_id: TokenId,
+        metadata: TokenMetadata,
+        owner: Option<ActorId>,
+    },
+    Balance {
+        token_id: TokenId,
+        account: ActorId,
+        amount: U256,
+    },
+}
+
+pub fn export(offset: u32, limit: u32) -> Vec<ItemRecord> {
+    let storage = ItemService::get_item();
+    let balances: &'static _ = Storage::balances();
+    let records = sorted_keys(&storage.token_metadata)
+        .into_iter()
+        .map(|token_id| ItemRecord::Metadata {
+            token_id,
+            metadata: storage.token_metadata[&token_id].clone(),
+            owner: storage.owners.get(&token_id).copied(),
+        })
+        .chain(sorted_keys(balances).into_iter().flat_map(move |token_id| {
+            let holders = &balances[&token_id];
+            sorted_keys(holders)
+                .into_iter()
+                .map(move |account| ItemRecord::Balance {
+                    token_id,
+                    account,
+                    amount: holders[&account],
+                })
+        }));
+    page(records, offset, limit)
+}
+
+/// Balances replace whatever the account holds, adjusting the total supply.
+pub fn import(records: Vec<ItemRecord>) -> Result<()> {
+    let storage = ItemService::get_item();
+    for record in records {
+        match record {
+            ItemRecord::Metadata {
+                token_id,
+                metadata,
+                owner,
+            } => {
+                storage.token_metadata.insert(token_id, metadata);
+                if let Some(owner) = owner {
+                    storage.owners.insert(token_id, owner);
+                }
+            }
+            ItemRecord::Balance {
+                token_id,
+                account,
+                amount,
+            } => {
+                let holders = Storage::balances().entry(token_id).or_default();
+                let previous = holders.get(&account).copied().unwrap_or_default();
+                let total_supply = Storage::total_supply().entry(token_id).or_default();
+                *total_supply = total_supply
+                    .checked_sub(previous)
+                    .and_then(|total| total.checked_add(amount))
+                    .ok_or(Error::NumericOverflow)?;
+                if amount.is_zero() {
+                    holders.remove(&account);
+                } else {
+                    holders.insert(account, amount);
+                }
+            }
+        }
+    }
+    Ok(())
+}
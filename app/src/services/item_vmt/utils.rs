@@ -13,6 +13,10 @@ pub enum Error {
     AmountGreaterThanOneForNft,
     WrongId,
     NotEnoughBalance,
+    MissingRole,
+    Paused,
+    Banned,
+    NumericOverflow,
 }
 
 #[derive(Debug, Decode, Encode, TypeInfo, Default, Clone, PartialEq, Eq)]
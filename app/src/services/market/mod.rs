@@ -4,9 +4,35 @@ use sails_rs::{
     gstd::service,
     prelude::*,
 };
-use crate::services::{gold_vft::GoldService, item_vmt::ItemService};
+use crate::services::{
+    self,
+    game::{session_key::SessionAction, GameService},
+    gold_vft::{accounting::GoldSource, GoldService},
+    item_vmt::ItemService,
+};
 use vmt_service::utils::TokenId;
-use crate::admin::Admins;
+use crate::admin::{
+    self, audit::AdminActionKind, rate_limit::RateLimitedAction, Admins, PauseTarget, Role,
+};
+pub mod snapshot;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum MarketError {
+    MissingRole,
+    /// Approvals are required, submit the change through an admin proposal.
+    ProposalRequired,
+    Paused,
+    Banned,
+    /// Called through a session key that may not buy.
+    SessionActionNotAllowed,
+    /// Too many purchases in the current window, retry from `retry_after` (ms).
+    RateLimited {
+        retry_after: u64,
+    },
+    PriceNotSet,
+    NumericOverflow,
+    InsufficientBalance,
+}
 
 #[derive(Encode, Decode, TypeInfo)]
 pub enum Event {
@@ -25,13 +51,10 @@ static mut MARKET_STORAGE: Option<MarketStorage> = None;
 pub struct MarketService {}
 
 impl MarketService {
-    pub fn seed() -> Self {
+    pub fn seed(prices: Vec<(TokenId, U256)>) -> Self {
         unsafe {
-            let mut prices_map: HashMap<TokenId, U256> = HashMap::new();
-            prices_map.insert(110.into(), 100.into());
-            prices_map.insert(220.into(), 200.into());
             MARKET_STORAGE = Some(MarketStorage {
-                prices: prices_map,
+                prices: prices.into_iter().collect(),
             });
         };
         MarketService {}
@@ -62,28 +85,44 @@ impl MarketService {
         Self {}
     }
 
-    pub fn set_price(&mut self, token_id: TokenId, price: U256) {
-        self.ensure_is_admin();
-        let storage = self.get_mut();
-        storage.prices.insert(token_id, price);
-        self.notify_on(Event::PriceSet { token_id, price })
-            .expect("Notification Error");
+    pub fn set_price(&mut self, token_id: TokenId, price: U256) -> Result<(), MarketError> {
+        self.check_role(Role::PriceManager)?;
+        if Admins::requires_proposal() {
+            return Err(MarketError::ProposalRequired);
+        }
+        self.set_price_internal(token_id, price);
+        admin::record_admin_action(AdminActionKind::PriceSet, &(token_id, price));
+        Ok(())
     }
 
-    pub fn buy(&mut self, token_id: TokenId, amount: U256) {
-        let buyer = msg::source();
-       
+    pub fn buy(&mut self, token_id: TokenId, amount: U256) -> Result<(), MarketError> {
+        if Admins::is_paused(PauseTarget::Market) {
+            return Err(MarketError::Paused);
+        }
+        let buyer = GameService::new()
+            .acting_player(SessionAction::Buy)
+            .map_err(|_| MarketError::SessionActionNotAllowed)?;
+        if Admins::is_banned(&buyer) {
+            return Err(MarketError::Banned);
+        }
         let storage = self.get();
-        let price = storage.prices.get(&token_id).expect("Price not set for token_id");
+        let price = storage.prices.get(&token_id).ok_or(MarketError::PriceNotSet)?;
 
-        let total_cost = *price * amount;
-        let buyer_balance = GoldService::balance_of_mine(buyer);
+        let total_cost = price
+            .checked_mul(amount)
+            .ok_or(MarketError::NumericOverflow)?;
+        // GOLD locked in vesting can't be spent either
+        let buyer_balance = GoldService::unlocked_balance_of(buyer);
 
         if buyer_balance < total_cost {
-            panic!("Insufficient balance");
+            return Err(MarketError::InsufficientBalance);
         }
+        Admins::check_rate_limit(buyer, RateLimitedAction::Buy)
+            .map_err(|retry_after| MarketError::RateLimited { retry_after })?;
 
-        GoldService::burn_internal_notify_off(buyer, total_cost);
+        services::utils::panicking(|| {
+            GoldService::new().burn_internal(buyer, total_cost, GoldSource::MarketPurchase)
+        });
         ItemService::mint_internal_notify_off(ItemService::get_item(), buyer, token_id, amount);
 
         self.notify_on(Event::Purchased {
@@ -93,6 +132,7 @@ impl MarketService {
             price: *price,
         })
         .expect("Notification Error");
+        Ok(())
     }
 
     pub fn get_price(&self, token_id: TokenId) -> Option<U256> {
@@ -102,9 +142,18 @@ impl MarketService {
 }
 
 impl MarketService {
-    fn ensure_is_admin(&self) {
-        if !Admins::is_admin(&msg::source()) {
-            panic!("Not admin")
-        };
+    fn check_role(&self, role: Role) -> Result<(), MarketError> {
+        if !Admins::has_role(&msg::source(), role) {
+            return Err(MarketError::MissingRole);
+        }
+        Ok(())
+    }
+
+    // Internal set price method without role check
+    pub fn set_price_internal(&mut self, token_id: TokenId, price: U256) {
+        let storage = self.get_mut();
+        storage.prices.insert(token_id, price);
+        self.notify_on(Event::PriceSet { token_id, price })
+            .expect("Notification Error");
     }
 }
\ No newline at end of file
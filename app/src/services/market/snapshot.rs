@@ -0,0 +1,31 @@
+use super::MarketService;
+use crate::services::utils::{page, sorted_keys};
+use sails_rs::prelude::*;
+use vmt_service::utils::TokenId;
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum MarketRecord {
+    Price { token_id: TokenId, price: U256 },
+}
+
+pub fn export(offset: u32, limit: u32) -> Vec<MarketRecord> {
+    let prices = &MarketService::new().get().prices;
+    let records = sorted_keys(prices)
+        .into_iter()
+        .map(|token_id| MarketRecord::Price {
+            token_id,
+            price: prices[&token_id],
+        });
+    page(records, offset, limit)
+}
+
+pub fn import(records: Vec<MarketRecord>) {
+    let prices = &mut MarketService::new().get_mut().prices;
+    for record in records {
+        match record {
+            MarketRecord::Price { token_id, price } => {
+                prices.insert(token_id, price);
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::admin::{self, audit::AdminActionKind, snapshot::AdminRecord, Admins, Role};
+use crate::services::{
+    self,
+    game::snapshot::{self as game_snapshot, GameRecord},
+    gold_vft::snapshot::{self as gold_snapshot, GoldRecord},
+    item_vmt::snapshot::{self as item_snapshot, ItemRecord},
+    market::snapshot::{self as market_snapshot, MarketRecord},
+};
+use gstd::msg;
+use sails_rs::{gstd::service, prelude::*};
+
+/// Most records returned by a single export query.
+const MAX_EXPORT_PAGE_SIZE: u32 = 100;
+
+/// Layout of the exported records. Bump it whenever a record changes shape,
+/// e.g. when `Player` gains a field. Every export page carries it and imports
+/// only accept pages of the same version.
+pub const STORAGE_VERSION: u32 = 1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Encode, Decode, TypeInfo)]
+pub enum SnapshotStorage {
+    Admin,
+    Gold,
+    Item,
+    Market,
+    Game,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum MigrationError {
+    /// Exports are only served to admins.
+    NotAdmin,
+    /// Imports are only accepted from the owner.
+    NotOwner,
+    /// Imports need the program to be in migration mode.
+    MigrationModeOff,
+    /// The records were exported with another `storage_version`.
+    StorageVersionMismatch,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub enum Event {
+    Imported {
+        storage: SnapshotStorage,
+        records: u32,
+    },
+}
+
+/// Moves state between program instances: the old one is exported page by
+/// page and replayed into a freshly deployed one, which can then be exported
+/// again and compared.
+#[derive(Clone)]
+pub struct MigrationService();
+
+#[service(events = Event)]
+impl MigrationService {
+    pub fn new() -> Self {
+        Self()
+    }
+
+    /// Each page comes with the `storage_version` it was exported in, which
+    /// the matching import checks.
+    pub fn export_admin(
+        &self,
+        offset: u32,
+        limit: u32,
+    ) -> Result<(u32, Vec<AdminRecord>), MigrationError> {
+        Self::ensure_admin()?;
+        Ok((
+            STORAGE_VERSION,
+            admin::snapshot::export(offset, limit.min(MAX_EXPORT_PAGE_SIZE)),
+        ))
+    }
+
+    pub fn export_gold(
+        &self,
+        offset: u32,
+        limit: u32,
+    ) -> Result<(u32, Vec<GoldRecord>), MigrationError> {
+        Self::ensure_admin()?;
+        Ok((
+            STORAGE_VERSION,
+            gold_snapshot::export(offset, limit.min(MAX_EXPORT_PAGE_SIZE)),
+        ))
+    }
+
+    pub fn export_items(
+        &self,
+        offset: u32,
+        limit: u32,
+    ) -> Result<(u32, Vec<ItemRecord>), MigrationError> {
+        Self::ensure_admin()?;
+        Ok((
+            STORAGE_VERSION,
+            item_snapshot::export(offset, limit.min(MAX_EXPORT_PAGE_SIZE)),
+        ))
+    }
+
+    pub fn export_market(
+        &self,
+        offset: u32,
+        limit: u32,
+    ) -> Result<(u32, Vec<MarketRecord>), MigrationError> {
+        Self::ensure_admin()?;
+        Ok((
+            STORAGE_VERSION,
+            market_snapshot::export(offset, limit.min(MAX_EXPORT_PAGE_SIZE)),
+        ))
+    }
+
+    pub fn export_game(
+        &self,
+        offset: u32,
+        limit: u32,
+    ) -> Result<(u32, Vec<GameRecord>), MigrationError> {
+        Self::ensure_admin()?;
+        Ok((
+            STORAGE_VERSION,
+            game_snapshot::export(offset, limit.min(MAX_EXPORT_PAGE_SIZE)),
+        ))
+    }
+
+    /// Layout version of the records this program exports and imports.
+    pub fn storage_version(&self) -> u32 {
+        STORAGE_VERSION
+    }
+
+    /// A batch with a threshold the imported admins can't reach is rejected
+    /// as a whole.
+    pub fn import_admin(
+        &mut self,
+        version: u32,
+        records: Vec<AdminRecord>,
+    ) -> Result<(), MigrationError> {
+        Self::ensure_can_import(version)?;
+        let count = records.len() as u32;
+        services::utils::panicking(|| admin::snapshot::import(records));
+        self.imported(SnapshotStorage::Admin, count);
+        Ok(())
+    }
+
+    /// A batch that would overflow the total supply or take it above the max
+    /// supply is rejected as a whole.
+    pub fn import_gold(
+        &mut self,
+        version: u32,
+        records: Vec<GoldRecord>,
+    ) -> Result<(), MigrationError> {
+        Self::ensure_can_import(version)?;
+        let count = records.len() as u32;
+        services::utils::panicking(|| gold_snapshot::import(records));
+        self.imported(SnapshotStorage::Gold, count);
+        Ok(())
+    }
+
+    /// A batch that would overflow a total supply is rejected as a whole.
+    pub fn import_items(
+        &mut self,
+        version: u32,
+        records: Vec<ItemRecord>,
+    ) -> Result<(), MigrationError> {
+        Self::ensure_can_import(version)?;
+        let count = records.len() as u32;
+        services::utils::panicking(|| item_snapshot::import(records));
+        self.imported(SnapshotStorage::Item, count);
+        Ok(())
+    }
+
+    pub fn import_market(
+        &mut self,
+        version: u32,
+        records: Vec<MarketRecord>,
+    ) -> Result<(), MigrationError> {
+        Self::ensure_can_import(version)?;
+        let count = records.len() as u32;
+        market_snapshot::import(records);
+        self.imported(SnapshotStorage::Market, count);
+        Ok(())
+    }
+
+    pub fn import_game(
+        &mut self,
+        version: u32,
+        records: Vec<GameRecord>,
+    ) -> Result<(), MigrationError> {
+        Self::ensure_can_import(version)?;
+        let count = records.len() as u32;
+        game_snapshot::import(records);
+        self.imported(SnapshotStorage::Game, count);
+        Ok(())
+    }
+}
+
+impl MigrationService {
+    fn ensure_admin() -> Result<(), MigrationError> {
+        if !Admins::is_admin(&msg::source()) {
+            return Err(MigrationError::NotAdmin);
+        }
+        Ok(())
+    }
+
+    fn ensure_can_import(version: u32) -> Result<(), MigrationError> {
+        if !Admins::has_role(&msg::source(), Role::Owner) {
+            return Err(MigrationError::NotOwner);
+        }
+        if !Admins::in_migration() {
+            return Err(MigrationError::MigrationModeOff);
+        }
+        if version != STORAGE_VERSION {
+            return Err(MigrationError::StorageVersionMismatch);
+        }
+        Ok(())
+    }
+
+    fn imported(&mut self, storage: SnapshotStorage, records: u32) {
+        self.notify_on(Event::Imported { storage, records })
+            .expect("Notification Error");
+        admin::record_admin_action(AdminActionKind::SnapshotImported, &(storage, records));
+    }
+}
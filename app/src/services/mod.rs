@@ -1,6 +1,8 @@
 pub mod admin;
+pub mod config;
 pub mod gold_vft;
 pub mod utils;
 pub mod item_vmt;
 pub mod market;
+pub mod migration;
 pub mod game;
\ No newline at end of file
@@ -1,5 +1,35 @@
 use core::fmt::Debug;
-use gstd::{ext, format};
+use gstd::{ext, format, msg, MessageId};
+use sails_rs::{
+    collections::HashMap,
+    gstd::services::{Exposure, ExposureCallScope},
+    prelude::*,
+};
+
+/// Routes of the services events are emitted for, the SCALE-encoded name
+/// each one is exposed under.
+pub const VFT_ROUTE: &[u8] = &[12, b'V', b'f', b't'];
+pub const ADMIN_ROUTE: &[u8] = &[20, b'A', b'd', b'm', b'i', b'n'];
+
+struct RouteExposure(&'static [u8]);
+
+impl Exposure for RouteExposure {
+    fn message_id(&self) -> MessageId {
+        msg::id()
+    }
+
+    fn route(&self) -> &'static [u8] {
+        self.0
+    }
+}
+
+/// Runs `f` as if the service at `route` handled the current message, so
+/// events a service emits while another one handles the call still reach
+/// its own listeners.
+pub fn on_route<T>(route: &'static [u8], f: impl FnOnce() -> T) -> T {
+    let _scope = ExposureCallScope::new(&RouteExposure(route));
+    f()
+}
 
 pub fn panicking<T, E: Debug, F: FnOnce() -> Result<T, E>>(f: F) -> T {
     match f() {
@@ -10,4 +40,15 @@ pub fn panicking<T, E: Debug, F: FnOnce() -> Result<T, E>>(f: F) -> T {
 
 pub fn panic(err: impl Debug) -> ! {
     ext::panic(&format!("{err:?}"))
-}
\ No newline at end of file
+}
+/// `limit` items of `items` starting at `offset`.
+pub fn page<T>(items: impl Iterator<Item = T>, offset: u32, limit: u32) -> Vec<T> {
+    items.skip(offset as usize).take(limit as usize).collect()
+}
+
+/// Keys of `map` in ascending order, so paginated exports are stable.
+pub fn sorted_keys<K: Ord + Copy, V>(map: &HashMap<K, V>) -> Vec<K> {
+    let mut keys: Vec<K> = map.keys().copied().collect();
+    keys.sort();
+    keys
+}
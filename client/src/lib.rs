@@ -2,3 +2,46 @@
 
 // Incorporate code generated based on the IDL file
 include!(concat!(env!("OUT_DIR"), "/animal_rogue_client.rs"));
+
+pub mod signing {
+    use super::*;
+    use sails_rs::prelude::*;
+
+    /// Must match `GAME_RESULT_VERSION` in the program.
+    pub const GAME_RESULT_VERSION: u8 = 2;
+
+    /// Must match `GAME_CHECKPOINT_VERSION` in the program.
+    pub const GAME_CHECKPOINT_VERSION: u8 = 1;
+
+    /// Must match `GAME_RESULT_CONTEXT` in the program.
+    pub const GAME_RESULT_CONTEXT: &[u8] = b"animal-rogue/game-result";
+
+    /// Must match `GAME_CHECKPOINT_CONTEXT` in the program.
+    pub const GAME_CHECKPOINT_CONTEXT: &[u8] = b"animal-rogue/game-checkpoint";
+
+    fn context(prefix: &[u8], program_id: ActorId) -> Vec<u8> {
+        let mut context = prefix.to_vec();
+        context.extend_from_slice(program_id.as_ref());
+        context
+    }
+
+    /// Signing context the verifier uses for results of the program at `program_id`.
+    pub fn game_result_context(program_id: ActorId) -> Vec<u8> {
+        context(GAME_RESULT_CONTEXT, program_id)
+    }
+
+    /// Signing context the verifier uses for checkpoints of the program at `program_id`.
+    pub fn game_checkpoint_context(program_id: ActorId) -> Vec<u8> {
+        context(GAME_CHECKPOINT_CONTEXT, program_id)
+    }
+
+    /// Message the verifier signs for `result`, byte for byte what `update_game` checks.
+    pub fn game_result_message(result: &GameResult) -> Vec<u8> {
+        result.encode()
+    }
+
+    /// Message the verifier signs for `checkpoint`, byte for byte what `checkpoint_game` checks.
+    pub fn game_checkpoint_message(checkpoint: &GameCheckpoint) -> Vec<u8> {
+        checkpoint.encode()
+    }
+}
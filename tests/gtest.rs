@@ -1,7 +1,8 @@
-use animal_rogue_client::{ traits::*, GameStatus, TokenMetadata};
+use animal_rogue_client::{ signing, traits::*, AdminActionKind, AdminError, AdminRecord, EmissionConfig, GameCheckpoint, GameError, GameResult, GameSettings, GameStatus, GoldError, GoldRecord, GoldSource, InitConfig, MarketError, MigrationError, PauseTarget, ProposalAction, RateLimit, RateLimitedAction, RewardTier, Role, SessionAction, SettingChange, SourceTotals, TokenConfig, TokenMetadata};
 use rand_core::OsRng;
+use animal_rogue_client::{admin::events::AdminEvents, vft::events::VftEvents};
 use sails_rs::{
-    calls::*, gtest::{calls::*, System}, hex, ActorId, U256
+    calls::*, events::Listener, futures::StreamExt, gtest::{calls::*, System}, hex, prelude::Encode, ActorId, U256
 };
 use schnorrkel::Keypair;
 use schnorrkel::{PublicKey, Signature};
@@ -11,6 +12,7 @@ const NEW_ADMIN_ID: u64 = 43;
 const RECIPIENT_ID: u64 = 44;
 
 const TOKEN_ID: u64 = 1;
+const VERIFIER_KEY_ID: u32 = 1;
 
 #[tokio::test]
 async fn add_admin_works() {
@@ -40,7 +42,7 @@ async fn add_admin_works() {
         .await
         .unwrap();
 
-    assert!(result);
+    assert_eq!(result, Ok(()));
 
     // Verify the new admin is added
     let is_admin = service_client
@@ -79,6 +81,7 @@ async fn remove_admin_works() {
         .add_admin(NEW_ADMIN_ID.into())
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
     // Remove the new admin
@@ -88,7 +91,7 @@ async fn remove_admin_works() {
         .await
         .unwrap();
 
-    assert!(result);
+    assert_eq!(result, Ok(()));
 
     // Verify the admin is removed
     let is_admin = service_client
@@ -132,12 +135,12 @@ async fn is_admin_works() {
     assert!(is_admin);
 }
 
-//   *******************************      gold_vft      *******************************
 #[tokio::test]
-async fn mint_works() {
+async fn roles_work() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(NEW_ADMIN_ID, 100_000_000_000_000);
 
     let remoting = GTestRemoting::new(system, ACTOR_ID.into());
     remoting.system().init_logger();
@@ -153,32 +156,77 @@ async fn mint_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Vft::new(remoting.clone());
+    let mut service_client = animal_rogue_client::Admin::new(remoting.clone());
 
-    // Mint tokens
+    // The deployer owns the program
+    let roles = service_client
+        .roles_of(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(roles, vec![Role::Owner]);
+
+    // Grant a single role
     let result = service_client
+        .grant_role(NEW_ADMIN_ID.into(), Role::Minter)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let roles = service_client
+        .roles_of(NEW_ADMIN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(roles, vec![Role::Minter]);
+
+    // A minter can mint GOLD but not set prices or hand out roles
+    let minter_remoting = remoting.clone().with_actor_id(NEW_ADMIN_ID.into());
+    let mut gold_client = animal_rogue_client::Vft::new(minter_remoting.clone());
+    let result = gold_client
         .mint(RECIPIENT_ID.into(), 1000.into())
         .send_recv(program_id)
         .await
         .unwrap();
+    assert!(result.is_ok(), "mint failed: {:?}", result);
 
-    assert!(result);
+    let mut market_client = animal_rogue_client::Market::new(minter_remoting.clone());
+    let result = market_client
+        .set_price(TOKEN_ID.into(), 100.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(MarketError::MissingRole));
 
-    // Verify the balance
-    let balance = service_client
-        .balance_of(RECIPIENT_ID.into())
-        .recv(program_id)
+    let mut minter_admin_client = animal_rogue_client::Admin::new(minter_remoting);
+    let result = minter_admin_client
+        .grant_role(RECIPIENT_ID.into(), Role::Minter)
+        .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(AdminError::NotOwner));
 
-    assert_eq!(balance, 1000.into());
+    // Revoke it again
+    let result = service_client
+        .revoke_role(NEW_ADMIN_ID.into(), Role::Minter)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let is_admin = service_client
+        .is_admin(NEW_ADMIN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert!(!is_admin);
 }
 
 #[tokio::test]
-async fn burn_works() {
+async fn proposals_work() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(NEW_ADMIN_ID, 100_000_000_000_000);
 
     let remoting = GTestRemoting::new(system, ACTOR_ID.into());
     remoting.system().init_logger();
@@ -194,39 +242,215 @@ async fn burn_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Vft::new(remoting.clone());
+    let mut service_client = animal_rogue_client::Admin::new(remoting.clone());
+    let result = service_client
+        .add_admin(NEW_ADMIN_ID.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
 
-    // Mint tokens first
-    service_client
-        .mint(RECIPIENT_ID.into(), 1000.into())
+    // Require two approvals from now on
+    let result = service_client
+        .set_governance(2, 60_000, 1000.into())
         .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Ok(()));
 
-    // Burn tokens
+    // Direct calls are refused
     let result = service_client
-        .burn(RECIPIENT_ID.into(), 500.into())
+        .set_governance(1, 60_000, 1000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::ProposalRequired));
+    let mut market_client = animal_rogue_client::Market::new(remoting.clone());
+    let result = market_client
+        .set_price(TOKEN_ID.into(), 100.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(MarketError::ProposalRequired));
+
+    // Propose the price change
+    let proposal_id = service_client
+        .propose(ProposalAction::SetPrice {
+            token_id: TOKEN_ID.into(),
+            price: 100.into(),
+        })
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let pending = service_client
+        .get_pending_proposals()
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].approvals, vec![ActorId::from(ACTOR_ID)]);
+
+    // The proposer cannot approve twice
+    let result = service_client
+        .approve(proposal_id)
         .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(AdminError::AlreadyApproved));
+    let price = market_client
+        .get_price(TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(price, None);
 
-    assert!(result);
+    // The second admin's approval executes it
+    let mut second_admin_client =
+        animal_rogue_client::Admin::new(remoting.clone().with_actor_id(NEW_ADMIN_ID.into()));
+    let result = second_admin_client
+        .approve(proposal_id)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let price = market_client
+        .get_price(TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(price, Some(100.into()));
 
-    // Verify the balance
-    let balance = service_client
-        .balance_of(RECIPIENT_ID.into())
+    // Proposals need the role of the call they stand for
+    let result = second_admin_client
+        .propose(ProposalAction::AddAdmin {
+            admin: RECIPIENT_ID.into(),
+        })
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::MissingRole));
+
+    // Actions that can't run are refused up front with their own error
+    let result = service_client
+        .propose(ProposalAction::GrantRole {
+            account: NEW_ADMIN_ID.into(),
+            role: Role::PriceManager,
+        })
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::RoleAlreadyGranted));
+    let result = service_client
+        .propose(ProposalAction::RevokeVerifierKey { key_id: 99 })
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::Game(GameError::VerifierKeyNotFound)));
+
+    let proposal = service_client
+        .get_proposal(proposal_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(proposal.executed);
+    let pending = service_client
+        .get_pending_proposals()
         .recv(program_id)
         .await
         .unwrap();
+    assert!(pending.is_empty());
 
-    assert_eq!(balance, 500.into());
+    // Large mints need a proposal as well
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let result = gold_client
+        .mint(RECIPIENT_ID.into(), 5000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GoldError::ProposalRequired));
+    let result = gold_client
+        .mint(RECIPIENT_ID.into(), 500.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert!(result.is_ok(), "mint failed: {:?}", result);
+
+    // Handing ownership to the other admin would leave one admin for two approvals
+    let result = service_client
+        .propose(ProposalAction::ProposeOwner {
+            new_owner: NEW_ADMIN_ID.into(),
+        })
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::LastAdmin));
+
+    // Owner actions are approved by any admin
+    let proposal_id = service_client
+        .propose(ProposalAction::AddAdmin {
+            admin: RECIPIENT_ID.into(),
+        })
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = second_admin_client
+        .approve(proposal_id)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let is_admin = service_client
+        .is_admin(RECIPIENT_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert!(is_admin);
+
+    // A threshold above the admin count is refused, a lower one goes through
+    let result = service_client
+        .propose(ProposalAction::SetGovernance {
+            approval_threshold: 4,
+            proposal_lifetime: 60_000,
+            large_mint_limit: 1000.into(),
+        })
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::InvalidThreshold));
+    let proposal_id = service_client
+        .propose(ProposalAction::SetGovernance {
+            approval_threshold: 1,
+            proposal_lifetime: 60_000,
+            large_mint_limit: 1000.into(),
+        })
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = second_admin_client
+        .approve(proposal_id)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let (approval_threshold, _, _) = service_client
+        .get_governance()
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(approval_threshold, 1);
 }
 
 #[tokio::test]
-async fn transfer_works() {
+async fn ownership_transfer_works() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(NEW_ADMIN_ID, 100_000_000_000_000);
+    system.mint_to(RECIPIENT_ID, 100_000_000_000_000);
 
     let remoting = GTestRemoting::new(system, ACTOR_ID.into());
     remoting.system().init_logger();
@@ -242,48 +466,87 @@ async fn transfer_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Vft::new(remoting.clone());
+    let mut service_client = animal_rogue_client::Admin::new(remoting.clone());
 
-    // Mint tokens first
-    service_client
-        .mint(ACTOR_ID.into(), 1000.into())
+    // The owner cannot be removed, and neither can the last admin
+    let result = service_client
+        .remove_admin(ACTOR_ID.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::CannotRemoveOwner));
+    let result = service_client
+        .grant_role(NEW_ADMIN_ID.into(), Role::Owner)
         .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(AdminError::OwnerRoleReserved));
 
-    // Transfer tokens
+    // Propose a new owner; only that account can accept
     let result = service_client
-        .transfer(RECIPIENT_ID.into(), 500.into())
+        .propose_owner(NEW_ADMIN_ID.into())
         .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Ok(()));
+    let pending_owner = service_client
+        .pending_owner()
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(pending_owner, Some(NEW_ADMIN_ID.into()));
 
-    assert!(result);
+    let mut other_client =
+        animal_rogue_client::Admin::new(remoting.clone().with_actor_id(RECIPIENT_ID.into()));
+    let result = other_client
+        .accept_ownership()
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::NotPendingOwner));
 
-    // Verify the balances
-    let balance_actor = service_client
-        .balance_of(ACTOR_ID.into())
+    let mut new_owner_client =
+        animal_rogue_client::Admin::new(remoting.clone().with_actor_id(NEW_ADMIN_ID.into()));
+    let result = new_owner_client
+        .accept_ownership()
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let owner = service_client.owner().recv(program_id).await.unwrap();
+    assert_eq!(owner, Some(NEW_ADMIN_ID.into()));
+    let is_admin = service_client
+        .is_admin(ACTOR_ID.into())
         .recv(program_id)
         .await
         .unwrap();
+    assert!(!is_admin);
 
-    let balance_recipient = service_client
-        .balance_of(RECIPIENT_ID.into())
-        .recv(program_id)
+    // The previous owner lost its rights
+    let result = service_client
+        .add_admin(RECIPIENT_ID.into())
+        .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(AdminError::NotOwner));
 
-    assert_eq!(balance_actor, 500.into());
-    assert_eq!(balance_recipient, 500.into());
+    // Renouncing is explicit
+    let result = new_owner_client
+        .renounce_ownership()
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let owner = service_client.owner().recv(program_id).await.unwrap();
+    assert_eq!(owner, None);
 }
 
-//   *******************************      item_vmt      *******************************
-
 #[tokio::test]
-async fn create_token_metadata_works() {
+async fn pause_works() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(NEW_ADMIN_ID, 100_000_000_000_000);
 
     let remoting = GTestRemoting::new(system, ACTOR_ID.into());
     remoting.system().init_logger();
@@ -299,43 +562,109 @@ async fn create_token_metadata_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+    let mut service_client = animal_rogue_client::Admin::new(remoting.clone());
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    gold_client
+        .mint(ACTOR_ID.into(), 1000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
 
-    // Create token metadata
-    let metadata = TokenMetadata {
-        title: Some("Sample title field".to_string()),
-        description: Some("Sample description".to_string()),
-        media: Some("Sample media".to_string()),
-        reference: Some("Sample reference".to_string()),
-        // Replace with actual field name and value
-    };
+    // Paused GOLD transfers are rejected
+    let result = service_client
+        .pause(PauseTarget::GoldTransfers)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let result = gold_client
+        .transfer(RECIPIENT_ID.into(), 100.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GoldError::Paused));
+
+    // Other services keep working
+    let mut market_client = animal_rogue_client::Market::new(remoting.clone());
+    let result = market_client
+        .buy(110.into(), 1.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert!(result.is_ok(), "buy failed: {:?}", result);
 
+    // Any admin can pause, only the owner resumes
     service_client
-        .create_token_metadata(TOKEN_ID.into(), metadata)
+        .add_admin(NEW_ADMIN_ID.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut admin_client =
+        animal_rogue_client::Admin::new(remoting.clone().with_actor_id(NEW_ADMIN_ID.into()));
+    let result = admin_client
+        .unpause(PauseTarget::GoldTransfers)
         .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(AdminError::NotOwner));
 
-    // Mint tokens
     let result = service_client
-        .mint(RECIPIENT_ID.into(), TOKEN_ID.into(), 1000.into()) // 假设 mint 方法存在
+        .unpause(PauseTarget::GoldTransfers)
         .send_recv(program_id)
-        .await;
-
-    assert!(result.is_ok());
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let result = gold_client
+        .transfer(RECIPIENT_ID.into(), 100.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(true));
 
-    // Verify the balance
-    let balance = service_client
-        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+    // The emergency stop halts everything
+    let result = admin_client
+        .set_emergency_stop(true)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let is_paused = service_client
+        .is_paused(PauseTarget::Market)
         .recv(program_id)
         .await
         .unwrap();
+    assert!(is_paused);
+    let result = market_client
+        .buy(110.into(), 1.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(MarketError::Paused));
 
-    assert_eq!(balance, 1000.into());
+    let result = admin_client
+        .set_emergency_stop(false)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::NotOwner));
+    let result = service_client
+        .set_emergency_stop(false)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let result = market_client
+        .buy(110.into(), 1.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert!(result.is_ok(), "buy failed: {:?}", result);
 }
 
 #[tokio::test]
-async fn vmt_mint_works() {
+async fn audit_log_works() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
@@ -354,104 +683,2520 @@ async fn vmt_mint_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
-
-    // Create token metadata
-    let metadata = TokenMetadata {
+    // Admin calls across services
+    let mut market_client = animal_rogue_client::Market::new(remoting.clone());
+    market_client
+        .set_price(TOKEN_ID.into(), 100.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut item_client = animal_rogue_client::Vmt::new(remoting.clone());
+    item_client
+        .create_token_metadata(
+            TOKEN_ID.into(),
+            TokenMetadata {
+                title: Some("Token".to_string()),
+                description: None,
+                media: None,
+                reference: None,
+            },
+        )
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+    game_client
+        .set_game_time(90)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let service_client = animal_rogue_client::Admin::new(remoting.clone());
+    let len = service_client.audit_log_len().recv(program_id).await.unwrap();
+    assert_eq!(len, 3);
+    let entries = service_client
+        .audit_log(0, 10)
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert!(entries.iter().all(|entry| entry.actor == ActorId::from(ACTOR_ID)));
+    assert_eq!(entries[0].id, 0);
+    assert!(entries[0].timestamp < entries[2].timestamp);
+    let kinds: Vec<AdminActionKind> = entries.into_iter().map(|entry| entry.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            AdminActionKind::PriceSet,
+            AdminActionKind::ItemMetadataCreated,
+            AdminActionKind::SettingChangeQueued,
+        ]
+    );
+
+    // Pagination
+    let entries = service_client
+        .audit_log(2, 10)
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].kind, AdminActionKind::SettingChangeQueued);
+}
+
+#[tokio::test]
+async fn bans_work() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(RECIPIENT_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let player_remoting = remoting.clone().with_actor_id(RECIPIENT_ID.into());
+    let mut player_game = animal_rogue_client::Game::new(player_remoting.clone());
+    player_game
+        .register_player("Cheater".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    gold_client
+        .mint(RECIPIENT_ID.into(), 1000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Admin::new(remoting.clone());
+    let result = service_client
+        .ban(ACTOR_ID.into(), "Forged client".to_string(), None)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::CannotBanAdmin));
+    let result = service_client
+        .ban(RECIPIENT_ID.into(), "Forged client".to_string(), None)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+
+    let bans = service_client.get_bans(0, 10).recv(program_id).await.unwrap();
+    assert_eq!(bans.len(), 1);
+    assert_eq!(bans[0].0, ActorId::from(RECIPIENT_ID));
+    assert_eq!(bans[0].1.reason, "Forged client");
+    assert_eq!(bans[0].1.banned_by, ActorId::from(ACTOR_ID));
+
+    // Banned players can't play or shop
+    let result = player_game.create_game().send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::Banned));
+    let mut player_market = animal_rogue_client::Market::new(player_remoting.clone());
+    let result = player_market
+        .buy(110.into(), 1.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(MarketError::Banned));
+
+    // Transfers are only frozen on request
+    let mut player_gold = animal_rogue_client::Vft::new(player_remoting.clone());
+    let result = player_gold
+        .transfer(ACTOR_ID.into(), 100.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(true));
+    service_client
+        .set_bans_block_transfers(true)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = player_gold
+        .transfer(ACTOR_ID.into(), 100.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GoldError::Banned));
+
+    // Temporary bans lapse on their own
+    let now = remoting.system().block_timestamp();
+    service_client
+        .ban(RECIPIENT_ID.into(), "Cooldown".to_string(), Some(now + 30_000))
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let ban = service_client
+        .get_ban(RECIPIENT_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(ban.map(|ban| ban.reason), Some("Cooldown".to_string()));
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 20);
+    let result = player_game.create_game().send_recv(program_id).await.unwrap();
+    assert!(result.is_ok(), "create_game failed: {:?}", result);
+    let result = service_client
+        .unban(RECIPIENT_ID.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::NotBanned));
+}
+
+#[tokio::test]
+async fn rate_limits_work() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(RECIPIENT_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let limit = || RateLimit {
+        max_calls: 2,
+        window: 60_000,
+    };
+    let mut outsider_client =
+        animal_rogue_client::Admin::new(remoting.clone().with_actor_id(RECIPIENT_ID.into()));
+    let result = outsider_client
+        .set_rate_limit(RateLimitedAction::CreateGame, limit())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(AdminError::MissingRole));
+
+    let mut service_client = animal_rogue_client::Admin::new(remoting.clone());
+    let result = service_client
+        .set_rate_limit(RateLimitedAction::CreateGame, limit())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+    // Calls rejected for other reasons don't count
+    for _ in 0..3 {
+        let result = game_client.create_game().send_recv(program_id).await.unwrap();
+        assert_eq!(result, Err(GameError::PlayerNotFound));
+    }
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    // Two games per minute
+    for _ in 0..2 {
+        let result = game_client.create_game().send_recv(program_id).await.unwrap();
+        assert!(result.is_ok(), "create_game failed: {:?}", result);
+    }
+    let now = remoting.system().block_timestamp();
+    let result = game_client.create_game().send_recv(program_id).await.unwrap();
+    let Err(GameError::RateLimited { retry_after }) = result else {
+        panic!("expected a rate limit, got {:?}", result);
+    };
+    assert!(retry_after > now);
+
+    // The window rolls over
+    let blocks = (retry_after - now) / 3000 + 1;
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + blocks as u32);
+    let result = game_client.create_game().send_recv(program_id).await.unwrap();
+    assert!(result.is_ok(), "create_game failed: {:?}", result);
+
+    // Only game creation is throttled
+    let limits = service_client.get_rate_limits().recv(program_id).await.unwrap();
+    assert_eq!(limits.len(), 1);
+    assert_eq!(limits[0].0, RateLimitedAction::CreateGame);
+}
+
+#[tokio::test]
+async fn init_config_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let keypair = Keypair::generate_with(OsRng);
+    let config = InitConfig {
+        admins: vec![NEW_ADMIN_ID.into()],
+        settings: GameSettings {
+            game_time: 90,
+            max_earn: 500,
+            initial_max_stamina: 10,
+            stamina_recovery_rate: 60_000,
+            grace_period: 10_000,
+            leaderboard_size: 10,
+            expiry_gas_limit: 0,
+            refund_stamina_on_expiry: true,
+            setting_change_delay: 0,
+            vested_reward_share: 20,
+            vesting_period: 60_000,
+        },
+        verifier_keys: vec![(VERIFIER_KEY_ID, keypair.public.to_bytes())],
+        gold: TokenConfig {
+            name: "Test Gold".to_string(),
+            symbol: "TGOLD".to_string(),
+            decimals: 4,
+        },
+        emission: EmissionConfig {
+            max_supply: 1_000_000.into(),
+            epoch_budget: 10_000.into(),
+        },
+        items: vec![(
+            TOKEN_ID.into(),
+            TokenMetadata {
+                title: Some("Potion".to_string()),
+                description: None,
+                media: None,
+                reference: None,
+            },
+        )],
+        prices: vec![(TOKEN_ID.into(), 50.into())],
+    };
+    let program_id = program_factory
+        .with_config(config) // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let admin_client = animal_rogue_client::Admin::new(remoting.clone());
+    let owner = admin_client.owner().recv(program_id).await.unwrap();
+    assert_eq!(owner, Some(ACTOR_ID.into()));
+    let is_admin = admin_client
+        .is_admin(NEW_ADMIN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert!(is_admin);
+
+    let gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let symbol = gold_client.symbol().recv(program_id).await.unwrap();
+    assert_eq!(symbol, "TGOLD");
+    let decimals = gold_client.decimals().recv(program_id).await.unwrap();
+    assert_eq!(decimals, 4);
+    let max_supply = gold_client.max_supply().recv(program_id).await.unwrap();
+    assert_eq!(max_supply, 1_000_000.into());
+
+    // Only the configured catalog is priced
+    let market_client = animal_rogue_client::Market::new(remoting.clone());
+    let price = market_client
+        .get_price(TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(price, Some(50.into()));
+    let price = market_client.get_price(110.into()).recv(program_id).await.unwrap();
+    assert_eq!(price, None);
+
+    let game_client = animal_rogue_client::Game::new(remoting.clone());
+    let settings = game_client.get_settings().recv(program_id).await.unwrap();
+    assert_eq!(settings.max_earn, 500);
+    assert_eq!(settings.leaderboard_size, 10);
+    let keys = game_client.get_verifier_keys().recv(program_id).await.unwrap();
+    assert_eq!(keys.len(), 1);
+    assert_eq!(keys[0].0, VERIFIER_KEY_ID);
+}
+
+#[tokio::test]
+async fn snapshot_migration_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let old_program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    // Give the old instance some state
+    let mut admin_client = animal_rogue_client::Admin::new(remoting.clone());
+    admin_client
+        .add_admin(NEW_ADMIN_ID.into())
+        .send_recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    gold_client
+        .mint(RECIPIENT_ID.into(), 1000.into())
+        .send_recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut market_client = animal_rogue_client::Market::new(remoting.clone());
+    market_client
+        .set_price(110.into(), 150.into())
+        .send_recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(old_program_id)
+        .await
+        .unwrap();
+    game_client
+        .create_game()
+        .send_recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Freeze the old instance and export it
+    admin_client
+        .set_migration_mode(true)
+        .send_recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = game_client.create_game().send_recv(old_program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::Paused));
+    // Player calls that change exported state are refused as well
+    let result = game_client
+        .update_player_info(Some("Renamed".to_string()), None, None)
+        .send_recv(old_program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::Paused));
+    let result = game_client
+        .register_player("Player2".to_string(), 2, "avatar2".to_string())
+        .send_recv(old_program_id)
+        .await;
+    assert!(result.is_err());
+
+    let mut migration_client = animal_rogue_client::Migration::new(remoting.clone());
+    let outsider_client =
+        animal_rogue_client::Migration::new(remoting.clone().with_actor_id(RECIPIENT_ID.into()));
+    let result = outsider_client
+        .export_gold(0, 100)
+        .recv(old_program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(MigrationError::NotAdmin));
+
+    let (version, admin_records) = migration_client
+        .export_admin(0, 100)
+        .recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let (_, gold_records) = migration_client
+        .export_gold(0, 100)
+        .recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let (_, item_records) = migration_client
+        .export_items(0, 100)
+        .recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let (_, market_records) = migration_client
+        .export_market(0, 100)
+        .recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let (_, game_records) = migration_client
+        .export_game(0, 100)
+        .recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    // Emission header, the AdminMint totals and the one balance
+    assert_eq!(gold_records.len(), 3);
+    assert!(game_records.len() < 100);
+
+    // Pages are stable
+    let (_, second) = migration_client
+        .export_game(1, 1)
+        .recv(old_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(second.encode(), game_records[1..2].encode());
+
+    let encoded = [
+        admin_records.encode(),
+        gold_records.encode(),
+        item_records.encode(),
+        market_records.encode(),
+        game_records.encode(),
+    ];
+
+    // Replay it into a new instance
+    let new_program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt2")
+        .await
+        .unwrap();
+    let result = migration_client
+        .import_gold(version, vec![])
+        .send_recv(new_program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(MigrationError::MigrationModeOff));
+    admin_client
+        .set_migration_mode(true)
+        .send_recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Pages of another storage version are refused
+    let result = migration_client
+        .import_admin(version + 1, vec![])
+        .send_recv(new_program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(MigrationError::StorageVersionMismatch));
+    // So is a threshold the imported admins couldn't reach
+    let result = migration_client
+        .import_admin(
+            version,
+            vec![AdminRecord::Governance {
+                approval_threshold: 5,
+                proposal_lifetime: 60_000,
+                large_mint_limit: 1000.into(),
+            }],
+        )
+        .send_recv(new_program_id)
+        .await;
+    assert!(result.is_err());
+    migration_client
+        .import_admin(version, admin_records)
+        .send_recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    migration_client
+        .import_gold(version, gold_records)
+        .send_recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    // A balance past the max supply rejects the whole batch
+    let result = migration_client
+        .import_gold(
+            version,
+            vec![GoldRecord::Balance {
+                account: RECIPIENT_ID.into(),
+                value: 100_000_000_001u64.into(),
+            }],
+        )
+        .send_recv(new_program_id)
+        .await;
+    assert!(result.is_err());
+    migration_client
+        .import_items(version, item_records)
+        .send_recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    migration_client
+        .import_market(version, market_records)
+        .send_recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    migration_client
+        .import_game(version, game_records)
+        .send_recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    admin_client
+        .set_migration_mode(false)
+        .send_recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // The new instance exports exactly what the old one did
+    let (_, migrated) = migration_client
+        .export_admin(0, 100)
+        .recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(migrated.encode(), encoded[0]);
+    let (_, migrated) = migration_client
+        .export_gold(0, 100)
+        .recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(migrated.encode(), encoded[1]);
+    let (_, migrated) = migration_client
+        .export_items(0, 100)
+        .recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(migrated.encode(), encoded[2]);
+    let (_, migrated) = migration_client
+        .export_market(0, 100)
+        .recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(migrated.encode(), encoded[3]);
+    let (_, migrated) = migration_client
+        .export_game(0, 100)
+        .recv(new_program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(migrated.encode(), encoded[4]);
+
+    let balance = gold_client
+        .balance_of(RECIPIENT_ID.into())
+        .recv(new_program_id)
+        .await
+        .unwrap();
+    assert_eq!(balance, 1000.into());
+    let result = game_client.create_game().send_recv(new_program_id).await.unwrap();
+    assert!(result.is_ok(), "create_game failed: {:?}", result);
+}
+
+#[tokio::test]
+async fn storage_version_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let migration_client = animal_rogue_client::Migration::new(remoting.clone());
+    let version = migration_client
+        .storage_version()
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(version, 1);
+}
+
+//   *******************************      gold_vft      *******************************
+#[tokio::test]
+async fn mint_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Vft::new(remoting.clone());
+
+    // Mint tokens
+    let result = service_client
+        .mint(RECIPIENT_ID.into(), 1000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(result, Ok(true));
+
+    // Verify the balance
+    let balance = service_client
+        .balance_of(RECIPIENT_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance, 1000.into());
+}
+
+#[tokio::test]
+async fn burn_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Vft::new(remoting.clone());
+
+    // Mint tokens first
+    service_client
+        .mint(RECIPIENT_ID.into(), 1000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Burn tokens
+    let result = service_client
+        .burn(RECIPIENT_ID.into(), 500.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(result, Ok(true));
+
+    // Verify the balance
+    let balance = service_client
+        .balance_of(RECIPIENT_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance, 500.into());
+}
+
+#[tokio::test]
+async fn transfer_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Vft::new(remoting.clone());
+
+    // Mint tokens first
+    service_client
+        .mint(ACTOR_ID.into(), 1000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Transfer tokens
+    let result = service_client
+        .transfer(RECIPIENT_ID.into(), 500.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(result, Ok(true));
+
+    // Verify the balances
+    let balance_actor = service_client
+        .balance_of(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    let balance_recipient = service_client
+        .balance_of(RECIPIENT_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance_actor, 500.into());
+    assert_eq!(balance_recipient, 500.into());
+}
+
+#[tokio::test]
+async fn emission_budget_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    // No mint can go past the max supply
+    let max_supply = gold_client.max_supply().recv(program_id).await.unwrap();
+    assert_eq!(max_supply, 100_000_000_000u64.into());
+    let result = gold_client
+        .mint(RECIPIENT_ID.into(), max_supply + 1)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GoldError::MaxSupplyExceeded));
+
+    // Only minters tune the budget
+    let mut other_client =
+        animal_rogue_client::Vft::new(remoting.clone().with_actor_id(RECIPIENT_ID.into()));
+    let result = other_client
+        .set_epoch_budget(1500.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GoldError::MissingRole));
+    let result = gold_client
+        .set_epoch_budget(1500.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 10, 1000.into()).await;
+    let budget = gold_client.emission_budget().recv(program_id).await.unwrap();
+    assert_eq!(budget.emitted, 1000.into());
+    assert_eq!(budget.remaining, 500.into());
+
+    // The next reward is scaled down to what is left
+    let game_id = play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 20, 1000.into()).await;
+    let settlement = game_client
+        .get_settlement(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(settlement.earn, 500.into());
+    let balance = gold_client.balance_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(balance, 1500.into());
+    let budget = gold_client.emission_budget().recv(program_id).await.unwrap();
+    assert_eq!(budget.remaining, 0.into());
+
+    // A new epoch brings a fresh budget
+    let now = remoting.system().block_timestamp();
+    let blocks = (budget.epoch_ends_at - now) / 3000 + 1;
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + blocks as u32);
+    let budget = gold_client.emission_budget().recv(program_id).await.unwrap();
+    assert_eq!(budget.emitted, 0.into());
+    assert_eq!(budget.remaining, 1500.into());
+
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 30, 1000.into()).await;
+    let balance = gold_client.balance_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(balance, 2500.into());
+}
+
+#[tokio::test]
+async fn supply_by_source_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+    let mut market_client = animal_rogue_client::Market::new(remoting.clone());
+
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    let mut gold_listener = animal_rogue_client::vft::events::listener(remoting.clone());
+    let mut gold_events = gold_listener.listen().await.unwrap();
+    let mut admin_listener = animal_rogue_client::admin::events::listener(remoting.clone());
+    let mut admin_events = admin_listener.listen().await.unwrap();
+
+    gold_client
+        .mint(ACTOR_ID.into(), 1000.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    gold_client
+        .burn(ACTOR_ID.into(), 200.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 10, 300.into()).await;
+    // Candy costs 100 GOLD in the default catalog
+    market_client
+        .buy(110.into(), 1.into())
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let totals = gold_client.supply_by_source().recv(program_id).await.unwrap();
+    let expected = vec![
+        (GoldSource::AdminMint, SourceTotals { minted: 1000.into(), burned: 0.into() }),
+        (GoldSource::AdminBurn, SourceTotals { minted: 0.into(), burned: 200.into() }),
+        (GoldSource::GameReward, SourceTotals { minted: 300.into(), burned: 0.into() }),
+        (GoldSource::MarketPurchase, SourceTotals { minted: 0.into(), burned: 100.into() }),
+    ];
+    assert_eq!(totals.encode(), expected.encode());
+
+    // The counters reconcile with the supply
+    let total_supply = gold_client.total_supply().recv(program_id).await.unwrap();
+    assert_eq!(total_supply, 1000.into());
+
+    // Every mint and burn is announced on the Vft route with its source, also
+    // when a game or market message caused it
+    let mut sources = vec![];
+    while sources.len() < 4 {
+        let (source_program, event) = gold_events.next().await.unwrap();
+        assert_eq!(source_program, program_id);
+        match event {
+            VftEvents::Minted { source, .. } => sources.push((true, source)),
+            VftEvents::Burned { source, .. } => sources.push((false, source)),
+            _ => {}
+        }
+    }
+    assert_eq!(
+        sources,
+        vec![
+            (true, GoldSource::AdminMint),
+            (false, GoldSource::AdminBurn),
+            (true, GoldSource::GameReward),
+            (false, GoldSource::MarketPurchase),
+        ]
+    );
+
+    // Admin actions recorded by the Vft service arrive on the Admin route
+    let mut kinds = vec![];
+    while kinds.last() != Some(&AdminActionKind::GoldBurned) {
+        let (_, event) = admin_events.next().await.unwrap();
+        if let AdminEvents::AdminAction { actor, kind, .. } = event {
+            assert_eq!(actor, ActorId::from(ACTOR_ID));
+            kinds.push(kind);
+        }
+    }
+    assert_eq!(kinds, vec![AdminActionKind::GoldMinted, AdminActionKind::GoldBurned]);
+}
+
+#[tokio::test]
+async fn vesting_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    // Lock half of every reward for a minute
+    let result = game_client
+        .set_vested_reward_share(101)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::InvalidSetting));
+    game_client
+        .set_vested_reward_share(50)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_vesting_period(60_000)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 10, 1000.into()).await;
+    let balance = gold_client.balance_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(balance, 1000.into());
+    let vesting = gold_client.vesting_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(vesting.locked, 500.into());
+    assert_eq!(vesting.schedules.len(), 1);
+
+    // Only the unlocked part can be transferred
+    let result = gold_client
+        .transfer(RECIPIENT_ID.into(), 600.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GoldError::BalanceLocked));
+    let result = gold_client
+        .transfer(RECIPIENT_ID.into(), 500.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(true));
+
+    // Part way through the period, some of the reward has vested
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 10);
+    let vesting = gold_client.vesting_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert!(vesting.claimable > 0.into() && vesting.claimable < 500.into());
+
+    // Vested GOLD stays locked until claimed
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 20);
+    let vesting = gold_client.vesting_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(vesting.locked, 500.into());
+    assert_eq!(vesting.claimable, 500.into());
+    let result = gold_client.claim_vested().send_recv(program_id).await.unwrap();
+    assert_eq!(result, Ok(500.into()));
+    let result = gold_client.claim_vested().send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GoldError::NothingToClaim));
+
+    let vesting = gold_client.vesting_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(vesting.locked, 0.into());
+    assert!(vesting.schedules.is_empty());
+    let result = gold_client
+        .transfer(RECIPIENT_ID.into(), 500.into())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(true));
+
+    // A later reward doesn't hold back an earlier one
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 20, 1000.into()).await;
+    let vesting = gold_client.vesting_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    let unlocks_at = vesting.schedules[0].start + vesting.schedules[0].duration;
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 30, 1000.into()).await;
+    let now = remoting.system().block_timestamp();
+    let blocks = unlocks_at.saturating_sub(now) / 3000 + 1;
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + blocks as u32);
+    let vesting = gold_client.vesting_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(vesting.schedules.len(), 2);
+    assert_eq!(vesting.locked, 1000.into());
+    assert!(vesting.claimable >= 500.into() && vesting.claimable < 1000.into());
+    let result = gold_client.claim_vested().send_recv(program_id).await.unwrap();
+    let Ok(claimed) = result else {
+        panic!("claim_vested failed: {:?}", result);
+    };
+    assert!(claimed >= 500.into());
+    let vesting = gold_client.vesting_of(ACTOR_ID.into()).recv(program_id).await.unwrap();
+    assert_eq!(vesting.schedules.len(), 1);
+}
+
+//   *******************************      item_vmt      *******************************
+
+#[tokio::test]
+async fn create_token_metadata_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+
+    // Create token metadata
+    let metadata = TokenMetadata {
+        title: Some("Sample title field".to_string()),
+        description: Some("Sample description".to_string()),
+        media: Some("Sample media".to_string()),
+        reference: Some("Sample reference".to_string()),
+        // Replace with actual field name and value
+    };
+
+    service_client
+        .create_token_metadata(TOKEN_ID.into(), metadata)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Mint tokens
+    let result = service_client
+        .mint(RECIPIENT_ID.into(), TOKEN_ID.into(), 1000.into()) // 假设 mint 方法存在
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert!(result.is_ok());
+
+    // Verify the balance
+    let balance = service_client
+        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance, 1000.into());
+}
+
+#[tokio::test]
+async fn vmt_mint_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+
+    // Create token metadata
+    let metadata = TokenMetadata {
+        title: Some("Sample title field".to_string()),
+        description: Some("Sample description".to_string()),
+        media: Some("Sample media".to_string()),
+        reference: Some("Sample reference".to_string()),
+        // Replace with actual field name and value
+    };
+    service_client
+        .create_token_metadata(TOKEN_ID.into(), metadata)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Mint tokens
+    let result = service_client
+        .mint(RECIPIENT_ID.into(), TOKEN_ID.into(), 1000.into()) // 假设 mint 方法存在
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert!(result.is_ok());
+
+    // Verify the balance
+    let balance = service_client
+        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance, 1000.into());
+}
+
+async fn vmt_mint_batch(
+    remoting: GTestRemoting,
+    recipt_id: u64,
+    program_id: ActorId,
+) -> animal_rogue_client::Vmt<GTestRemoting> {
+    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+
+    // Create token metadata
+    let metadata_1 = TokenMetadata {
+        title: Some("Sample title field 1".to_string()),
+        description: Some("Sample description 1".to_string()),
+        media: Some("Sample media 1".to_string()),
+        reference: Some("Sample reference 1".to_string()),
+    };
+    let metadata_2 = TokenMetadata {
+        title: Some("Sample title field 2".to_string()),
+        description: Some("Sample description 2".to_string()),
+        media: Some("Sample media 2".to_string()),
+        reference: Some("Sample reference 2".to_string()),
+    };
+    service_client
+        .create_token_metadata(TOKEN_ID.into(), metadata_1)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    service_client
+        .create_token_metadata((TOKEN_ID + 1).into(), metadata_2)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Mint batch tokens
+    let result = service_client
+        .mint_batch(
+            recipt_id.into(),
+            vec![TOKEN_ID.into(), (TOKEN_ID + 1).into()],
+            vec![1000.into(), 2000.into()],
+        ) // 假设 mint_batch 方法存在
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert!(result.is_ok());
+
+    // Verify the balances
+    let balance_1 = service_client
+        .balance_of(recipt_id.into(), TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    let balance_2 = service_client
+        .balance_of(recipt_id.into(), (TOKEN_ID + 1).into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance_1, 1000.into());
+    assert_eq!(balance_2, 2000.into());
+
+    service_client
+}
+
+#[tokio::test]
+async fn vmt_mint_batch_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    vmt_mint_batch(remoting, RECIPIENT_ID, program_id).await;
+}
+
+#[tokio::test]
+async fn vmt_burn_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+
+    // Create token metadata
+    let metadata = TokenMetadata {
         title: Some("Sample title field".to_string()),
         description: Some("Sample description".to_string()),
         media: Some("Sample media".to_string()),
         reference: Some("Sample reference".to_string()),
         // Replace with actual field name and value
     };
-    service_client
-        .create_token_metadata(TOKEN_ID.into(), metadata)
+    service_client
+        .create_token_metadata(TOKEN_ID.into(), metadata)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Mint tokens first
+    service_client
+        .mint(RECIPIENT_ID.into(), TOKEN_ID.into(), 1000.into()) // 假设 mint 方法存在
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Burn tokens
+    let result = service_client
+        .burn(RECIPIENT_ID.into(), TOKEN_ID.into(), 500.into()) // 假设 burn 方法存在
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert!(result.is_ok(), "Burn failed: {:?}", result);
+    // Verify the balance
+    let balance = service_client
+        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance, 500.into());
+}
+
+#[tokio::test]
+async fn vmt_burn_batch_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+
+    // Create token metadata
+    let metadata_1 = TokenMetadata {
+        title: Some("Sample title field 1".to_string()),
+        description: Some("Sample description 1".to_string()),
+        media: Some("Sample media 1".to_string()),
+        reference: Some("Sample reference 1".to_string()),
+    };
+    let metadata_2 = TokenMetadata {
+        title: Some("Sample title field 2".to_string()),
+        description: Some("Sample description 2".to_string()),
+        media: Some("Sample media 2".to_string()),
+        reference: Some("Sample reference 2".to_string()),
+    };
+    service_client
+        .create_token_metadata(TOKEN_ID.into(), metadata_1)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    service_client
+        .create_token_metadata((TOKEN_ID + 1).into(), metadata_2)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Mint batch tokens first
+    service_client
+        .mint_batch(
+            RECIPIENT_ID.into(),
+            vec![TOKEN_ID.into(), (TOKEN_ID + 1).into()],
+            vec![1000.into(), 2000.into()],
+        )
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Burn batch tokens
+    let result = service_client
+        .burn_batch(
+            RECIPIENT_ID.into(),
+            vec![TOKEN_ID.into(), (TOKEN_ID + 1).into()],
+            vec![500.into(), 1000.into()],
+        )
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert!(result.is_ok(), "Burn batch failed: {:?}", result);
+
+    // Verify the balances
+    let balance_1 = service_client
+        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    let balance_2 = service_client
+        .balance_of(RECIPIENT_ID.into(), (TOKEN_ID + 1).into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance_1, 500.into());
+    assert_eq!(balance_2, 1000.into());
+}
+
+//   *******************************      market      *******************************
+
+#[tokio::test]
+async fn market_set_price_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut service_client = animal_rogue_client::Market::new(remoting.clone());
+
+    // Set price for a token
+    let price: U256 = 100.into();
+    let result = service_client
+        .set_price(TOKEN_ID.into(), price)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert!(result.is_ok(), "set_price failed: {:?}", result);
+
+    // Verify the price is set correctly
+    let stored_price = service_client
+        .get_price(TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(stored_price.unwrap(), price);
+}
+
+#[tokio::test]
+async fn market_buy_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut market_client = animal_rogue_client::Market::new(remoting.clone());
+    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let item_client = animal_rogue_client::Vmt::new(remoting.clone());
+
+    // Set price for a token
+    let price: U256 = 100.into();
+    market_client
+        .set_price(TOKEN_ID.into(), price)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Mint some gold tokens to the buyer
+    let gold_amount: U256 = 1000.into();
+    gold_client
+        .mint(ACTOR_ID.into(), gold_amount)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Perform a buy operation
+    let amount_to_buy: U256 = 5.into();
+    let result = market_client
+        .buy(TOKEN_ID.into(), amount_to_buy)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    assert!(result.is_ok(), "buy failed: {:?}", result);
+
+    // Verify the buyer's gold balance after purchase
+    let buyer_balance_after = gold_client
+        .balance_of(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    let total_cost = price * amount_to_buy;
+    assert_eq!(buyer_balance_after, gold_amount - total_cost);
+
+    // Verify the buyer's item balance
+    let item_balance = item_client
+        .balance_of(ACTOR_ID.into(), TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(item_balance, amount_to_buy);
+}
+
+//   *******************************      game      *******************************
+
+#[tokio::test]
+async fn create_game_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+
+    let stamina = 10;
+    let single_block_time = 3000;
+    let set_stamina_recovery_rate = single_block_time * 10;
+    let mut current_block = 0;
+
+    // Set game settings
+    game_client
+        .set_game_time(60)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_max_earn(1000)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_initial_max_stamina(stamina)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_stamina_recovery_rate(set_stamina_recovery_rate)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Register a player
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    // Create a game
+    let result = game_client.create_game().send_recv(program_id).await.unwrap();
+    assert!(result.is_ok(), "create_game failed: {:?}", result);
+    current_block += 1;
+
+    let game_id = result.unwrap();
+    // Verify the game is created
+    let game = game_client
+        .get_game(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.stage, 0);
+    assert_eq!(
+        game.time,
+        game_client
+            .get_settings()
+            .recv(program_id)
+            .await
+            .unwrap()
+            .game_time
+    );
+    current_block += 2;
+    assert_eq!(game.status, GameStatus::Created);
+    assert_eq!(game.score, 0);
+    assert_eq!(game.creator, ACTOR_ID.into());
+
+    // Check player recovered_block after block advancement
+    let recovered_block = game_client
+        .get_player_recovered_block()
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        recovered_block,
+        set_stamina_recovery_rate - current_block * single_block_time
+    );
+
+    current_block += 1;
+
+    // Check player stamina after block advancement
+    let stamina_after_blocks = game_client
+        .get_player_stamina()
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stamina_after_blocks, stamina - 1);
+
+    current_block += 1;
+
+    // after 5 blocks, stamina should be recovered
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 5 as u32);
+
+    current_block += 5;
+    let recovered_block = game_client
+        .get_player_recovered_block()
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        recovered_block,
+        set_stamina_recovery_rate - current_block * single_block_time
+    );
+
+    let stamina_after_blocks = game_client
+        .get_player_stamina()
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stamina_after_blocks, stamina);
+
+    // Create another game
+    let result = game_client.create_game().send_recv(program_id).await.unwrap();
+
+    assert!(result.is_ok(), "create_game failed: {:?}", result);
+    let game_id = result.unwrap();
+
+    // Verify the game is created
+    let game = game_client
+        .get_game(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.stage, 0);
+    assert_eq!(
+        game.time,
+        game_client
+            .get_settings()
+            .recv(program_id)
+            .await
+            .unwrap()
+            .game_time
+    );
+    assert_eq!(game.status, GameStatus::Created);
+    assert_eq!(game.score, 0);
+    assert_eq!(game.creator, ACTOR_ID.into());
+
+    // Check player stamina after creating another game
+    let stamina_after_blocks = game_client
+        .get_player_stamina()
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stamina_after_blocks, stamina - 1);
+
+    // Create another game
+    let result = game_client.create_game().send_recv(program_id).await.unwrap();
+
+    assert!(result.is_ok(), "create_game failed: {:?}", result);
+
+    // Check player stamina after creating another game
+    let stamina_after_blocks = game_client
+        .get_player_stamina()
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(stamina_after_blocks, stamina - 2);
+}
+
+fn sign_game_result(keypair: &Keypair, program_id: ActorId, result: &GameResult) -> Vec<u8> {
+    keypair
+        .sign_simple(
+            &signing::game_result_context(program_id),
+            &signing::game_result_message(result),
+        )
+        .to_bytes()
+        .to_vec()
+}
+
+fn sign_game_checkpoint(
+    keypair: &Keypair,
+    program_id: ActorId,
+    checkpoint: &GameCheckpoint,
+) -> Vec<u8> {
+    keypair
+        .sign_simple(
+            &signing::game_checkpoint_context(program_id),
+            &signing::game_checkpoint_message(checkpoint),
+        )
+        .to_bytes()
+        .to_vec()
+}
+
+async fn play_game(
+    game_client: &mut animal_rogue_client::Game<GTestRemoting>,
+    keypair: &Keypair,
+    program_id: ActorId,
+    player: u64,
+    score: i32,
+    earn: U256,
+) -> u32 {
+    let game_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+    game_client
+        .start_game(game_id)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let game_result = || GameResult {
+        version: signing::GAME_RESULT_VERSION,
+        game_id,
+        creator: player.into(),
+        stage: 1,
+        score,
+        earn,
+        token_ids: vec![],
+        amounts: vec![],
+        deadline: u64::MAX,
+    };
+    let signature = sign_game_result(keypair, program_id, &game_result());
+    game_client
+        .update_game(game_result(), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_id
+}
+
+#[tokio::test]
+async fn update_game_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+
+    // Mint the item
+    let item_service_client = vmt_mint_batch(remoting.clone(), ACTOR_ID, program_id).await;
+
+    // Set game settings
+    game_client
+        .set_game_time(60)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_max_earn(1000)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_initial_max_stamina(100)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_stamina_recovery_rate(1000000)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Register a player
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    // Generate keypair
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+
+    // Register the verifier key
+    let public_key = keypair.public.to_bytes().to_vec();
+    let result = game_client
+        .add_verifier_key(VERIFIER_KEY_ID, public_key, 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+
+    // Create a game
+    let result = game_client.create_game().send_recv(program_id).await.unwrap();
+
+    assert!(result.is_ok(), "create_game failed: {:?}", result);
+    let game_id = result.unwrap();
+
+    // Start the game
+    let result = game_client.start_game(game_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Ok(()));
+
+    // Prepare signature
+    let game_result = || GameResult {
+        version: signing::GAME_RESULT_VERSION,
+        game_id,
+        creator: ACTOR_ID.into(),
+        stage: 1,
+        score: 100,
+        earn: U256::from(50),
+        token_ids: vec![TOKEN_ID.into()],
+        amounts: vec![U256::from(10)],
+        deadline: u64::MAX,
+    };
+    let signature = sign_game_result(&keypair, program_id, &game_result());
+
+    // A signature over different content is rejected
+    let mut tampered = game_result();
+    tampered.earn = U256::from(2000);
+    let result = game_client
+        .update_game(tampered, VERIFIER_KEY_ID, signature.clone())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::InvalidSignature));
+
+    // Update the game
+    let result = game_client
+        .update_game(game_result(), VERIFIER_KEY_ID, signature.clone())
+        .send_recv(program_id)
+        .await;
+    assert!(result.is_ok(), "update_game failed: {:?}", result);
+    assert_eq!(result.unwrap(), Ok(()));
+
+    // Replaying the same signature must not pay out twice
+    let result = game_client
+        .update_game(game_result(), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::GameAlreadySettled));
+
+    // The settlement record is kept
+    let settlement = game_client
+        .get_settlement(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(settlement.creator, ACTOR_ID.into());
+    assert_eq!(settlement.score, 100);
+    assert_eq!(settlement.earn, 50.into());
+
+    // Verify the game is updated
+    let game = game_client
+        .get_game(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.score, 100);
+    assert_eq!(game.status, GameStatus::Ended);
+
+    // Verify the player's highest score and games played
+    let player = game_client
+        .get_player(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(player.highest_score, 100);
+    assert_eq!(player.games_played, 1);
+
+    // Verify the vmt item's balance
+    let balance_1 = item_service_client
+        .balance_of(ACTOR_ID.into(), TOKEN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance_1, 990.into());
+
+    // Verify the gold token balance
+    let token_service_client = animal_rogue_client::Vft::new(remoting.clone());
+    // Verify the balance
+    let balance = token_service_client
+        .balance_of(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+
+    assert_eq!(balance, 50.into());
+
+    // Verify the leaderboard
+    let leaderboard = game_client
+        .get_leaderboard(0, 10)
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(leaderboard.len(), 1);
+    assert_eq!(leaderboard[0].1, 100);
+
+}
+
+#[tokio::test]
+async fn checkpoint_game_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Register a player and start a game
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    let game_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+    game_client
+        .start_game(game_id)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let checkpoint = |stage| GameCheckpoint {
+        version: signing::GAME_CHECKPOINT_VERSION,
+        game_id,
+        creator: ACTOR_ID.into(),
+        stage,
+        partial_score: 50,
+        partial_earn: U256::from(20),
+        deadline: u64::MAX,
+    };
+
+    // Reach stage 2
+    let signature = sign_game_checkpoint(&keypair, program_id, &checkpoint(2));
+    let result = game_client
+        .checkpoint_game(checkpoint(2), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+
+    // Stages only move forward
+    let signature = sign_game_checkpoint(&keypair, program_id, &checkpoint(1));
+    let result = game_client
+        .checkpoint_game(checkpoint(1), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::StageNotIncreasing));
+
+    let game = game_client
+        .get_game(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.stage, 2);
+    assert_eq!(game.score, 50);
+
+    let game_result = |score| GameResult {
+        version: signing::GAME_RESULT_VERSION,
+        game_id,
+        creator: ACTOR_ID.into(),
+        stage: 3,
+        score,
+        earn: U256::from(30),
+        token_ids: vec![],
+        amounts: vec![],
+        deadline: u64::MAX,
+    };
+
+    // A final score below the checkpoint is refused
+    let signature = sign_game_result(&keypair, program_id, &game_result(40));
+    let result = game_client
+        .update_game(game_result(40), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::InconsistentWithCheckpoint));
+
+    let signature = sign_game_result(&keypair, program_id, &game_result(80));
+    let result = game_client
+        .update_game(game_result(80), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+
+    let game = game_client
+        .get_game(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.stage, 3);
+    assert_eq!(game.score, 80);
+    assert_eq!(game.status, GameStatus::Ended);
+}
+
+#[tokio::test]
+async fn paused_games_keep_their_checkpoint() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+    let mut admin_client = animal_rogue_client::Admin::new(remoting.clone());
+
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    // Expire games by hand
+    game_client
+        .set_expiry_gas_limit(0)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    let game_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+    let idle_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+    game_client
+        .start_game(game_id)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let checkpoint = |stage| GameCheckpoint {
+        version: signing::GAME_CHECKPOINT_VERSION,
+        game_id,
+        creator: ACTOR_ID.into(),
+        stage,
+        partial_score: 50,
+        partial_earn: U256::from(20),
+        deadline: u64::MAX,
+    };
+    let signature = sign_game_checkpoint(&keypair, program_id, &checkpoint(1));
+    game_client
+        .checkpoint_game(checkpoint(1), VERIFIER_KEY_ID, signature)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
-    // Mint tokens
-    let result = service_client
-        .mint(RECIPIENT_ID.into(), TOKEN_ID.into(), 1000.into()) // 假设 mint 方法存在
+    admin_client
+        .set_emergency_stop(true)
         .send_recv(program_id)
-        .await;
+        .await
+        .unwrap()
+        .unwrap();
 
-    assert!(result.is_ok());
+    // Running games can't progress
+    let result = game_client.start_game(idle_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::Paused));
+    let signature = sign_game_checkpoint(&keypair, program_id, &checkpoint(2));
+    let result = game_client
+        .checkpoint_game(checkpoint(2), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::Paused));
 
-    // Verify the balance
-    let balance = service_client
-        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+    // Expired games stay open until games resume
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 35);
+    let result = game_client.expire_game(game_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::Paused));
+    let game = game_client
+        .get_game(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.status, GameStatus::InProgress);
+
+    // And then pay their checkpoint
+    admin_client
+        .set_emergency_stop(false)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = game_client.expire_game(game_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Ok(()));
+    let game = game_client
+        .get_game(game_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.status, GameStatus::Expired);
+    let settlement = game_client
+        .get_settlement(game_id)
         .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
+    assert_eq!(settlement.earn, 20.into());
 
-    assert_eq!(balance, 1000.into());
+    let gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let balance = gold_client
+        .balance_of(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(balance, 20.into());
 }
 
-async fn vmt_mint_batch(
-    remoting: GTestRemoting,
-    recipt_id: u64,
-    program_id: ActorId,
-) -> animal_rogue_client::Vmt<GTestRemoting> {
-    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+#[tokio::test]
+async fn banned_creators_earn_nothing() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(RECIPIENT_ID, 100_000_000_000_000);
 
-    // Create token metadata
-    let metadata_1 = TokenMetadata {
-        title: Some("Sample title field 1".to_string()),
-        description: Some("Sample description 1".to_string()),
-        media: Some("Sample media 1".to_string()),
-        reference: Some("Sample reference 1".to_string()),
-    };
-    let metadata_2 = TokenMetadata {
-        title: Some("Sample title field 2".to_string()),
-        description: Some("Sample description 2".to_string()),
-        media: Some("Sample media 2".to_string()),
-        reference: Some("Sample reference 2".to_string()),
-    };
-    service_client
-        .create_token_metadata(TOKEN_ID.into(), metadata_1)
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+    let mut admin_client = animal_rogue_client::Admin::new(remoting.clone());
+
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-    service_client
-        .create_token_metadata((TOKEN_ID + 1).into(), metadata_2)
+    // Expire games by hand
+    game_client
+        .set_expiry_gas_limit(0)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
-    // Mint batch tokens
-    let result = service_client
-        .mint_batch(
-            recipt_id.into(),
-            vec![TOKEN_ID.into(), (TOKEN_ID + 1).into()],
-            vec![1000.into(), 2000.into()],
-        ) // 假设 mint_batch 方法存在
+    let mut player_game =
+        animal_rogue_client::Game::new(remoting.clone().with_actor_id(RECIPIENT_ID.into()));
+    player_game
+        .register_player("Cheater".to_string(), 1, "avatar1".to_string())
         .send_recv(program_id)
-        .await;
+        .await
+        .unwrap();
+    let game_id = player_game.create_game().send_recv(program_id).await.unwrap().unwrap();
+    let idle_id = player_game.create_game().send_recv(program_id).await.unwrap().unwrap();
+    player_game
+        .start_game(game_id)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
 
-    assert!(result.is_ok());
+    let checkpoint = |stage| GameCheckpoint {
+        version: signing::GAME_CHECKPOINT_VERSION,
+        game_id,
+        creator: RECIPIENT_ID.into(),
+        stage,
+        partial_score: 50,
+        partial_earn: U256::from(20),
+        deadline: u64::MAX,
+    };
+    let signature = sign_game_checkpoint(&keypair, program_id, &checkpoint(1));
+    player_game
+        .checkpoint_game(checkpoint(1), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
 
-    // Verify the balances
-    let balance_1 = service_client
-        .balance_of(recipt_id.into(), TOKEN_ID.into())
-        .recv(program_id)
+    admin_client
+        .ban(RECIPIENT_ID.into(), "Forged client".to_string(), None)
+        .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
-    let balance_2 = service_client
-        .balance_of(recipt_id.into(), (TOKEN_ID + 1).into())
+    // Games created before the ban can't be started
+    let result = player_game.start_game(idle_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::Banned));
+
+    // The running game can't record progress any more
+    let signature = sign_game_checkpoint(&keypair, program_id, &checkpoint(2));
+    let result = player_game
+        .checkpoint_game(checkpoint(2), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::Banned));
+
+    // Nor pay its checkpoint once it expires
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 35);
+    let result = game_client.expire_game(game_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Ok(()));
+    let settlement = game_client.get_settlement(game_id).recv(program_id).await.unwrap();
+    assert!(settlement.is_none());
+
+    let gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let balance = gold_client
+        .balance_of(RECIPIENT_ID.into())
         .recv(program_id)
         .await
         .unwrap();
+    assert_eq!(balance, 0.into());
+}
 
-    assert_eq!(balance_1, 1000.into());
-    assert_eq!(balance_2, 2000.into());
+#[tokio::test]
+async fn verifier_key_registry_works() {
+    let system = System::new();
+    system.init_logger();
+    system.mint_to(ACTOR_ID, 100_000_000_000_000);
 
-    service_client
+    let remoting = GTestRemoting::new(system, ACTOR_ID.into());
+    remoting.system().init_logger();
+
+    // Submit program code into the system
+    let program_code_id = remoting.system().submit_code(animal_rogue::WASM_BINARY);
+
+    let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
+
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+
+    // Malformed keys are refused at registration
+    let result = game_client
+        .add_verifier_key(VERIFIER_KEY_ID, vec![0; 5], 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::InvalidPublicKey));
+
+    // Register the old and the new key side by side
+    let old_keypair: Keypair = Keypair::generate_with(OsRng);
+    let new_keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, old_keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let result = game_client
+        .add_verifier_key(VERIFIER_KEY_ID, new_keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::VerifierKeyExists));
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID + 1, new_keypair.public.to_bytes().to_vec(), 0, None)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let keys = game_client.get_verifier_keys().recv(program_id).await.unwrap();
+    assert_eq!(keys.len(), 3);
+
+    // Register a player and create a game
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    let game_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+    game_client
+        .start_game(game_id)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let game_result = || GameResult {
+        version: signing::GAME_RESULT_VERSION,
+        game_id,
+        creator: ACTOR_ID.into(),
+        stage: 1,
+        score: 10,
+        earn: U256::from(5),
+        token_ids: vec![],
+        amounts: vec![],
+        deadline: u64::MAX,
+    };
+
+    // The revoked key no longer settles games
+    game_client
+        .revoke_verifier_key(VERIFIER_KEY_ID)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let signature = sign_game_result(&old_keypair, program_id, &game_result());
+    let result = game_client
+        .update_game(game_result(), VERIFIER_KEY_ID, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::VerifierKeyInactive));
+
+    // While the rotated-in key still does
+    let signature = sign_game_result(&new_keypair, program_id, &game_result());
+    let result = game_client
+        .update_game(game_result(), VERIFIER_KEY_ID + 1, signature)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
 }
 
 #[tokio::test]
-async fn vmt_mint_batch_works() {
+async fn game_lifecycle_works() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
@@ -464,17 +3209,107 @@ async fn vmt_mint_batch_works() {
 
     let program_factory = animal_rogue_client::AnimalRogueFactory::new(remoting.clone());
 
-    let program_id = program_factory
-        .new() // Call program's constructor
-        .send_recv(program_code_id, b"salt")
+    let program_id = program_factory
+        .new() // Call program's constructor
+        .send_recv(program_code_id, b"salt")
+        .await
+        .unwrap();
+
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+
+    // 60 seconds of play plus 30 seconds of grace, i.e. 30 blocks
+    game_client
+        .set_game_time(60)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_grace_period(30_000)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // Register a player
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
+
+    let started_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+    let idle_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+
+    let open_games = game_client
+        .get_open_games(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(open_games.len(), 2);
+
+    // Start the first game, only once
+    let result = game_client.start_game(started_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Ok(()));
+    let result = game_client.start_game(started_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::InvalidGameStatus));
+
+    let game = game_client
+        .get_game(started_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.status, GameStatus::InProgress);
+    assert!(game.started_at.is_some());
+
+    // Nothing can be expired before its time is up
+    let result = game_client.expire_game(started_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::GameNotExpired));
+
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 35);
+
+    let result = game_client.expire_game(started_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Ok(()));
+    let result = game_client.expire_game(idle_id).send_recv(program_id).await.unwrap();
+    assert_eq!(result, Ok(()));
+
+    let game = game_client
+        .get_game(started_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.status, GameStatus::Expired);
+    let game = game_client
+        .get_game(idle_id)
+        .recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(game.status, GameStatus::Abandoned);
+
+    let open_games = game_client
+        .get_open_games(ACTOR_ID.into())
+        .recv(program_id)
         .await
         .unwrap();
+    assert!(open_games.is_empty());
 
-    vmt_mint_batch(remoting, RECIPIENT_ID, program_id).await;
+    // No reward was paid
+    let gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let balance = gold_client
+        .balance_of(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(balance, 0.into());
 }
 
 #[tokio::test]
-async fn vmt_burn_works() {
+async fn stale_game_expires_automatically() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
@@ -493,48 +3328,66 @@ async fn vmt_burn_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
 
-    // Create token metadata
-    let metadata = TokenMetadata {
-        title: Some("Sample title field".to_string()),
-        description: Some("Sample description".to_string()),
-        media: Some("Sample media".to_string()),
-        reference: Some("Sample reference".to_string()),
-        // Replace with actual field name and value
-    };
-    service_client
-        .create_token_metadata(TOKEN_ID.into(), metadata)
+    // Short games so the expiry message fires within a few blocks
+    game_client
+        .set_game_time(3)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .set_grace_period(3_000)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-
-    // Mint tokens first
-    service_client
-        .mint(RECIPIENT_ID.into(), TOKEN_ID.into(), 1000.into()) // 假设 mint 方法存在
+    game_client
+        .set_refund_stamina_on_expiry(true)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
-    // Burn tokens
-    let result = service_client
-        .burn(RECIPIENT_ID.into(), TOKEN_ID.into(), 500.into()) // 假设 burn 方法存在
+    // Register a player
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
         .send_recv(program_id)
-        .await;
+        .await
+        .unwrap();
+    let max_stamina = game_client.get_player_stamina().recv(program_id).await.unwrap().unwrap();
 
-    assert!(result.is_ok(), "Burn failed: {:?}", result);
-    // Verify the balance
-    let balance = service_client
-        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+    let game_id = game_client.create_game().send_recv(program_id).await.unwrap().unwrap();
+    let stamina = game_client.get_player_stamina().recv(program_id).await.unwrap().unwrap();
+    assert_eq!(stamina, max_stamina - 1);
+
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 10);
+
+    // The delayed self-message closed the game and gave the stamina back
+    let game = game_client
+        .get_game(game_id)
         .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
+    assert_eq!(game.status, GameStatus::Abandoned);
 
-    assert_eq!(balance, 500.into());
+    let stamina = game_client.get_player_stamina().recv(program_id).await.unwrap().unwrap();
+    assert_eq!(stamina, max_stamina);
+
+    let open_games = game_client
+        .get_open_games(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert!(open_games.is_empty());
 }
 
 #[tokio::test]
-async fn vmt_burn_batch_works() {
+async fn season_leaderboard_works() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
@@ -553,76 +3406,75 @@ async fn vmt_burn_batch_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Vmt::new(remoting.clone());
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
 
-    // Create token metadata
-    let metadata_1 = TokenMetadata {
-        title: Some("Sample title field 1".to_string()),
-        description: Some("Sample description 1".to_string()),
-        media: Some("Sample media 1".to_string()),
-        reference: Some("Sample reference 1".to_string()),
-    };
-    let metadata_2 = TokenMetadata {
-        title: Some("Sample title field 2".to_string()),
-        description: Some("Sample description 2".to_string()),
-        media: Some("Sample media 2".to_string()),
-        reference: Some("Sample reference 2".to_string()),
-    };
-    service_client
-        .create_token_metadata(TOKEN_ID.into(), metadata_1)
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-    service_client
-        .create_token_metadata((TOKEN_ID + 1).into(), metadata_2)
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
         .send_recv(program_id)
         .await
         .unwrap();
 
-    // Mint batch tokens first
-    service_client
-        .mint_batch(
-            RECIPIENT_ID.into(),
-            vec![TOKEN_ID.into(), (TOKEN_ID + 1).into()],
-            vec![1000.into(), 2000.into()],
-        )
+    // Open a season
+    let season_id = game_client
+        .create_season(0, u64::MAX, vec![])
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-
-    // Burn batch tokens
-    let result = service_client
-        .burn_batch(
-            RECIPIENT_ID.into(),
-            vec![TOKEN_ID.into(), (TOKEN_ID + 1).into()],
-            vec![500.into(), 1000.into()],
-        )
+    let result = game_client
+        .create_season(1, 2, vec![])
         .send_recv(program_id)
-        .await;
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::SeasonOverlap));
 
-    assert!(result.is_ok(), "Burn batch failed: {:?}", result);
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 70, 10.into()).await;
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 50, 10.into()).await;
 
-    // Verify the balances
-    let balance_1 = service_client
-        .balance_of(RECIPIENT_ID.into(), TOKEN_ID.into())
+    // Only the best score of the season counts
+    let leaderboard = game_client
+        .get_season_leaderboard(season_id, 0, 10)
         .recv(program_id)
         .await
         .unwrap();
+    assert_eq!(leaderboard, vec![(ACTOR_ID.into(), 70)]);
 
-    let balance_2 = service_client
-        .balance_of(RECIPIENT_ID.into(), (TOKEN_ID + 1).into())
-        .recv(program_id)
+    // Close the season, freezing the standings
+    let result = game_client
+        .close_season(season_id)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(()));
+    let result = game_client
+        .close_season(season_id)
+        .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(GameError::SeasonClosed));
 
-    assert_eq!(balance_1, 500.into());
-    assert_eq!(balance_2, 1000.into());
-}
+    let current = game_client.get_current_season().recv(program_id).await.unwrap();
+    assert!(current.is_none());
 
-//   *******************************      market      *******************************
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 90, 10.into()).await;
+
+    let leaderboard = game_client
+        .get_season_leaderboard(season_id, 0, 10)
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(leaderboard, vec![(ACTOR_ID.into(), 70)]);
+}
 
 #[tokio::test]
-async fn market_set_price_works() {
+async fn distribute_season_rewards_works() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
@@ -641,32 +3493,134 @@ async fn market_set_price_works() {
         .await
         .unwrap();
 
-    let mut service_client = animal_rogue_client::Market::new(remoting.clone());
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
+    let gold_client = animal_rogue_client::Vft::new(remoting.clone());
+    let item_client = animal_rogue_client::Vmt::new(remoting.clone());
 
-    // Set price for a token
-    let price: U256 = 100.into();
-    let result = service_client
-        .set_price(TOKEN_ID.into(), price)
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
         .send_recv(program_id)
-        .await;
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .send_recv(program_id)
+        .await
+        .unwrap();
 
-    assert!(result.is_ok(), "set_price failed: {:?}", result);
+    let season_id = game_client
+        .create_season(0, u64::MAX, vec![])
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    play_game(&mut game_client, &keypair, program_id, ACTOR_ID, 70, 10.into()).await;
 
-    // Verify the price is set correctly
-    let stored_price = service_client
-        .get_price(TOKEN_ID.into())
+    // Rewards can't be paid while the season is running
+    let result = game_client
+        .distribute_season_rewards(season_id, 10)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::SeasonNotClosed));
+
+    // Ranks beyond the leaderboard would never be paid
+    let result = game_client
+        .set_season_rewards(
+            season_id,
+            vec![RewardTier {
+                from_rank: 1,
+                to_rank: 101,
+                gold: 500.into(),
+                token_ids: vec![],
+                amounts: vec![],
+            }],
+        )
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::InvalidRewardTier));
+
+    let rewards = vec![RewardTier {
+        from_rank: 1,
+        to_rank: 3,
+        gold: 500.into(),
+        token_ids: vec![110.into()],
+        amounts: vec![2.into()],
+    }];
+    game_client
+        .set_season_rewards(season_id, rewards)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    game_client
+        .close_season(season_id)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let result = game_client
+        .distribute_season_rewards(season_id, 0)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::InvalidBatchSize));
+
+    let result = game_client
+        .distribute_season_rewards(season_id, 10)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Ok(1));
+    let admin_client = animal_rogue_client::Admin::new(remoting.clone());
+    let len = admin_client.audit_log_len().recv(program_id).await.unwrap();
+    let entries = admin_client
+        .audit_log(len - 1, 1)
         .recv(program_id)
         .await
         .unwrap();
+    assert_eq!(entries[0].kind, AdminActionKind::SeasonRewardsDistributed);
 
-    assert_eq!(stored_price.unwrap(), price);
+    // Nobody is paid twice
+    let result = game_client
+        .distribute_season_rewards(season_id, 10)
+        .send_recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(result, Err(GameError::RewardsAlreadyDistributed));
+
+    let paid = game_client
+        .is_season_reward_paid(season_id, ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert!(paid);
+
+    let balance = gold_client
+        .balance_of(ACTOR_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(balance, 510.into());
+    let item_balance = item_client
+        .balance_of(ACTOR_ID.into(), 110.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(item_balance, 2.into());
 }
 
 #[tokio::test]
-async fn market_buy_works() {
+async fn leaderboard_is_bounded_and_ranked() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(NEW_ADMIN_ID, 100_000_000_000_000);
+    system.mint_to(RECIPIENT_ID, 100_000_000_000_000);
 
     let remoting = GTestRemoting::new(system, ACTOR_ID.into());
     remoting.system().init_logger();
@@ -682,59 +3636,99 @@ async fn market_buy_works() {
         .await
         .unwrap();
 
-    let mut market_client = animal_rogue_client::Market::new(remoting.clone());
-    let mut gold_client = animal_rogue_client::Vft::new(remoting.clone());
-    let item_client = animal_rogue_client::Vmt::new(remoting.clone());
+    let mut game_client = animal_rogue_client::Game::new(remoting.clone());
 
-    // Set price for a token
-    let price: U256 = 100.into();
-    market_client
-        .set_price(TOKEN_ID.into(), price)
+    let keypair: Keypair = Keypair::generate_with(OsRng);
+    game_client
+        .add_verifier_key(VERIFIER_KEY_ID, keypair.public.to_bytes().to_vec(), 0, None)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
-    // Mint some gold tokens to the buyer
-    let gold_amount: U256 = 1000.into();
-    gold_client
-        .mint(ACTOR_ID.into(), gold_amount)
+    // Only the top two are kept
+    game_client
+        .set_leaderboard_size(2)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-
-    // Perform a buy operation
-    let amount_to_buy: U256 = 5.into();
-    let result = market_client
-        .buy(TOKEN_ID.into(), amount_to_buy)
+    let season_id = game_client
+        .create_season(0, u64::MAX, vec![])
         .send_recv(program_id)
-        .await;
+        .await
+        .unwrap()
+        .unwrap();
 
-    assert!(result.is_ok(), "buy failed: {:?}", result);
+    for (player, score) in [(ACTOR_ID, 50), (RECIPIENT_ID, 80), (NEW_ADMIN_ID, 50)] {
+        let mut player_client =
+            animal_rogue_client::Game::new(remoting.clone().with_actor_id(player.into()));
+        player_client
+            .register_player(format!("Player{player}"), 1, "avatar".to_string())
+            .send_recv(program_id)
+            .await
+            .unwrap();
+        play_game(&mut player_client, &keypair, program_id, player, score, 10.into()).await;
+    }
 
-    // Verify the buyer's gold balance after purchase
-    let buyer_balance_after = gold_client
-        .balance_of(ACTOR_ID.into())
+    // The tie goes to whoever reached the score first
+    let leaderboard = game_client
+        .get_leaderboard(0, 10)
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        leaderboard,
+        vec![(RECIPIENT_ID.into(), 80), (ACTOR_ID.into(), 50)]
+    );
+
+    let page = game_client
+        .get_leaderboard(1, 1)
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(page, vec![(ACTOR_ID.into(), 50)]);
+
+    let rank = game_client
+        .get_player_rank(ACTOR_ID.into())
         .recv(program_id)
         .await
         .unwrap();
+    assert_eq!(rank, Some(2));
+    let rank = game_client
+        .get_player_rank(NEW_ADMIN_ID.into())
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(rank, None);
 
-    let total_cost = price * amount_to_buy;
-    assert_eq!(buyer_balance_after, gold_amount - total_cost);
+    let players = game_client.get_players(0, 2).recv(program_id).await.unwrap();
+    assert_eq!(players.len(), 2);
+    assert_eq!(players[0].0, ACTOR_ID.into());
 
-    // Verify the buyer's item balance
-    let item_balance = item_client
-        .balance_of(ACTOR_ID.into(), TOKEN_ID.into())
+    // Shrinking the board also shrinks the running season's
+    game_client
+        .set_leaderboard_size(1)
+        .send_recv(program_id)
+        .await
+        .unwrap()
+        .unwrap();
+    let leaderboard = game_client
+        .get_leaderboard(0, 10)
         .recv(program_id)
         .await
         .unwrap();
-
-    assert_eq!(item_balance, amount_to_buy);
+    assert_eq!(leaderboard, vec![(RECIPIENT_ID.into(), 80)]);
+    let leaderboard = game_client
+        .get_season_leaderboard(season_id, 0, 10)
+        .recv(program_id)
+        .await
+        .unwrap();
+    assert_eq!(leaderboard, vec![(RECIPIENT_ID.into(), 80)]);
 }
 
-//   *******************************      game      *******************************
-
 #[tokio::test]
-async fn create_game_works() {
+async fn setting_changes_are_timelocked() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
@@ -755,168 +3749,100 @@ async fn create_game_works() {
 
     let mut game_client = animal_rogue_client::Game::new(remoting.clone());
 
-    let stamina = 10;
-    let single_block_time = 3000;
-    let set_stamina_recovery_rate = single_block_time * 10;
-    let mut current_block = 0;
-
-    // Set game settings
+    // Without a delay changes apply immediately
     game_client
-        .set_game_time(60)
+        .set_max_earn(1000)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-    game_client
-        .set_max_earn(1000)
+    let settings = game_client.get_settings().recv(program_id).await.unwrap();
+    assert_eq!(settings.max_earn, 1000);
+
+    // Out of range values are refused
+    let result = game_client
+        .set_stamina_recovery_rate(0)
         .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(GameError::InvalidSetting));
+
+    // Require a minute of notice from now on
+    let now = remoting.system().block_timestamp();
     game_client
-        .set_initial_max_stamina(stamina)
+        .queue_setting_change(SettingChange::ChangeDelay(60_000), now + 30_000)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-    game_client
-        .set_stamina_recovery_rate(set_stamina_recovery_rate)
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 10);
+    let applied = game_client
+        .apply_setting_changes()
         .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(applied, 1);
 
-    // Register a player
+    // Now a change waits in the queue
     game_client
-        .register_player("Player1".to_string(), 1, "avatar1".to_string())
+        .set_max_earn(500)
         .send_recv(program_id)
         .await
-        .unwrap();
-
-    // Create a game
-    let result = game_client.create_game().send_recv(program_id).await;
-    assert!(result.is_ok(), "create_game failed: {:?}", result);
-    current_block += 1;
-
-    let game_id = result.unwrap();
-    // Verify the game is created
-    let game = game_client
-        .get_game(game_id)
-        .recv(program_id)
-        .await
         .unwrap()
         .unwrap();
-    assert_eq!(game.stage, 0);
-    assert_eq!(
-        game.time,
-        game_client
-            .get_settings()
-            .recv(program_id)
-            .await
-            .unwrap()
-            .game_time
-    );
-    current_block += 2;
-    assert_eq!(game.status, GameStatus::Created);
-    assert_eq!(game.score, 0);
-    assert_eq!(game.creator, ACTOR_ID.into());
-
-    // Check player recovered_block after block advancement
-    let recovered_block = game_client
-        .get_player_recovered_block()
-        .recv(program_id)
-        .await
-        .unwrap();
-    assert_eq!(
-        recovered_block,
-        set_stamina_recovery_rate - current_block * single_block_time
-    );
-
-    current_block += 1;
-
-    // Check player stamina after block advancement
-    let stamina_after_blocks = game_client
-        .get_player_stamina()
-        .recv(program_id)
-        .await
-        .unwrap();
-    assert_eq!(stamina_after_blocks, stamina - 1);
-
-    current_block += 1;
-
-    // after 5 blocks, stamina should be recovered
-    remoting
-        .system()
-        .run_to_block(remoting.system().block_height() + 5 as u32);
-
-    current_block += 5;
-    let recovered_block = game_client
-        .get_player_recovered_block()
+    let settings = game_client.get_settings().recv(program_id).await.unwrap();
+    assert_eq!(settings.max_earn, 1000);
+    let pending = game_client
+        .get_pending_setting_changes()
         .recv(program_id)
         .await
         .unwrap();
-    assert_eq!(
-        recovered_block,
-        set_stamina_recovery_rate - current_block * single_block_time
-    );
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].change, SettingChange::MaxEarn(500));
 
-    let stamina_after_blocks = game_client
-        .get_player_stamina()
-        .recv(program_id)
+    let now = remoting.system().block_timestamp();
+    let result = game_client
+        .queue_setting_change(SettingChange::MaxEarn(1), now + 1_000)
+        .send_recv(program_id)
         .await
         .unwrap();
-    assert_eq!(stamina_after_blocks, stamina);
-
-    // Create another game
-    let result = game_client.create_game().send_recv(program_id).await;
-
-    assert!(result.is_ok(), "create_game failed: {:?}", result);
-    let game_id = result.unwrap();
+    assert_eq!(result, Err(GameError::ActivationTooEarly));
 
-    // Verify the game is created
-    let game = game_client
-        .get_game(game_id)
-        .recv(program_id)
+    // Cancel it and queue another value
+    game_client
+        .cancel_setting_change(pending[0].id)
+        .send_recv(program_id)
         .await
         .unwrap()
         .unwrap();
-    assert_eq!(game.stage, 0);
-    assert_eq!(
-        game.time,
-        game_client
-            .get_settings()
-            .recv(program_id)
-            .await
-            .unwrap()
-            .game_time
-    );
-    assert_eq!(game.status, GameStatus::Created);
-    assert_eq!(game.score, 0);
-    assert_eq!(game.creator, ACTOR_ID.into());
-
-    // Check player stamina after creating another game
-    let stamina_after_blocks = game_client
-        .get_player_stamina()
+    let pending = game_client
+        .get_pending_setting_changes()
         .recv(program_id)
         .await
         .unwrap();
-    assert_eq!(stamina_after_blocks, stamina - 1);
-
-    // Create another game
-    let result = game_client.create_game().send_recv(program_id).await;
+    assert!(pending.is_empty());
 
-    assert!(result.is_ok(), "create_game failed: {:?}", result);
-
-    // Check player stamina after creating another game
-    let stamina_after_blocks = game_client
-        .get_player_stamina()
-        .recv(program_id)
+    game_client
+        .set_max_earn(700)
+        .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-    assert_eq!(stamina_after_blocks, stamina - 2);
+    remoting
+        .system()
+        .run_to_block(remoting.system().block_height() + 25);
+    let settings = game_client.get_settings().recv(program_id).await.unwrap();
+    assert_eq!(settings.max_earn, 700);
 }
 
 #[tokio::test]
-async fn update_game_works() {
+async fn session_keys_work() {
     let system = System::new();
     system.init_logger();
     system.mint_to(ACTOR_ID, 100_000_000_000_000);
+    system.mint_to(RECIPIENT_ID, 100_000_000_000_000);
 
     let remoting = GTestRemoting::new(system, ACTOR_ID.into());
     remoting.system().init_logger();
@@ -933,119 +3859,85 @@ async fn update_game_works() {
         .unwrap();
 
     let mut game_client = animal_rogue_client::Game::new(remoting.clone());
-
-    // Mint the item
-    let item_service_client = vmt_mint_batch(remoting.clone(), ACTOR_ID, program_id).await;
-
-    // Set game settings
-    game_client
-        .set_game_time(60)
-        .send_recv(program_id)
-        .await
-        .unwrap();
     game_client
-        .set_max_earn(1000)
+        .register_player("Player1".to_string(), 1, "avatar1".to_string())
         .send_recv(program_id)
         .await
         .unwrap();
-    game_client
-        .set_initial_max_stamina(100)
+
+    // Expiry is bounded
+    let now = remoting.system().block_timestamp();
+    let result = game_client
+        .register_session_key(RECIPIENT_ID.into(), now, vec![SessionAction::CreateGame])
         .send_recv(program_id)
         .await
         .unwrap();
-    game_client
-        .set_stamina_recovery_rate(1000000)
+    assert_eq!(result, Err(GameError::InvalidSessionExpiry));
+    let result = game_client
+        .register_session_key(
+            RECIPIENT_ID.into(),
+            now + 60_000,
+            vec![SessionAction::CreateGame],
+        )
         .send_recv(program_id)
         .await
         .unwrap();
-
-    // Register a player
-    game_client
-        .register_player("Player1".to_string(), 1, "avatar1".to_string())
-        .send_recv(program_id)
+    assert_eq!(result, Ok(()));
+    let session_key = game_client
+        .get_session_key(RECIPIENT_ID.into())
+        .recv(program_id)
         .await
+        .unwrap()
         .unwrap();
+    assert_eq!(session_key.owner, ActorId::from(ACTOR_ID));
 
-    // Generate keypair
-    let keypair: Keypair = Keypair::generate_with(OsRng);
-
-    // Set verifier public key
-    let public_key = keypair.public.to_bytes().to_vec();
-    game_client
-        .set_verifier_public_key(public_key.clone())
+    // The key plays for its owner
+    let session_remoting = remoting.clone().with_actor_id(RECIPIENT_ID.into());
+    let mut session_game = animal_rogue_client::Game::new(session_remoting.clone());
+    let game_id = session_game
+        .create_game()
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-
-    // Create a game
-    let result = game_client.create_game().send_recv(program_id).await;
-
-    assert!(result.is_ok(), "create_game failed: {:?}", result);
-    let game_id = result.unwrap();
-
-    // Prepare signature
-    let message_str = format!("{}{}{}", game_id, 100, U256::from(50));
-    let message = message_str.as_bytes();
-    let signature = keypair.sign_simple(b"substrate", &message).to_bytes().to_vec();
-
-    // Update the game
-    let token_ids = vec![TOKEN_ID.into()];
-    let amounts = vec![U256::from(10)];
-    let result = game_client
-        .update_game(game_id, 100, U256::from(50), signature, token_ids, amounts)
-        .send_recv(program_id)
-        .await;
-    assert!(result.is_ok(), "update_game failed: {:?}", result);
-
-    // Verify the game is updated
-    let game = game_client
-        .get_game(game_id)
+    let open_games = game_client
+        .get_open_games(ACTOR_ID.into())
         .recv(program_id)
         .await
-        .unwrap()
         .unwrap();
-    assert_eq!(game.score, 100);
-    assert_eq!(game.status, GameStatus::Ended);
-
-    // Verify the player's highest score and games played
-    let player = game_client
-        .get_player(ACTOR_ID.into())
-        .recv(program_id)
+    assert_eq!(open_games.len(), 1);
+    assert_eq!(open_games[0].0, game_id);
+    let result = session_game
+        .start_game(game_id)
+        .send_recv(program_id)
         .await
-        .unwrap()
         .unwrap();
-    assert_eq!(player.highest_score, 100);
-    assert_eq!(player.games_played, 1);
+    assert_eq!(result, Ok(()));
 
-    // Verify the vmt item's balance
-    let balance_1 = item_service_client
-        .balance_of(ACTOR_ID.into(), TOKEN_ID.into())
-        .recv(program_id)
+    // but only for the actions it was given
+    let mut session_market = animal_rogue_client::Market::new(session_remoting.clone());
+    let result = session_market
+        .buy(110.into(), 1.into())
+        .send_recv(program_id)
         .await
         .unwrap();
+    assert_eq!(result, Err(MarketError::SessionActionNotAllowed));
 
-    assert_eq!(balance_1, 990.into());
-
-    // Verify the gold token balance
-    let token_service_client = animal_rogue_client::Vft::new(remoting.clone());
-    // Verify the balance
-    let balance = token_service_client
-        .balance_of(ACTOR_ID.into())
-        .recv(program_id)
+    // Once revoked the key is just another account
+    game_client
+        .revoke_session_key(RECIPIENT_ID.into())
+        .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
-
-    assert_eq!(balance, 50.into());
-
-    // Verify the leaderboard
-    let leaderboard = game_client
-        .get_leaderboard()
-        .recv(program_id)
+    let result = session_game.create_game().send_recv(program_id).await.unwrap();
+    assert_eq!(result, Err(GameError::PlayerNotFound));
+    let result = game_client
+        .revoke_session_key(RECIPIENT_ID.into())
+        .send_recv(program_id)
         .await
         .unwrap();
-    assert_eq!(leaderboard.len(), 1);
-    assert_eq!(leaderboard[0].1, 100);
-
+    assert_eq!(result, Err(GameError::SessionKeyNotFound));
 }
 
 #[tokio::test]
@@ -1075,21 +3967,25 @@ async fn register_player_works() {
         .set_game_time(60)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     game_client
         .set_max_earn(1000)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     game_client
         .set_initial_max_stamina(100)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     game_client
         .set_stamina_recovery_rate(10)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
     // Register a player
@@ -1158,21 +4054,25 @@ async fn update_player_info_works() {
         .set_game_time(60)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     game_client
         .set_max_earn(1000)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     game_client
         .set_initial_max_stamina(100)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
     game_client
         .set_stamina_recovery_rate(10)
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
     // Register a player
@@ -1191,6 +4091,7 @@ async fn update_player_info_works() {
         )
         .send_recv(program_id)
         .await
+        .unwrap()
         .unwrap();
 
     // Verify the player info is updated